@@ -0,0 +1,197 @@
+use aoc2021::runner::{expected_answers, find, input_files, latest_year, solutions, Solution};
+use std::collections::HashMap;
+use std::env;
+use std::fs::metadata;
+use std::fs::read_to_string;
+use std::panic;
+use std::path::PathBuf;
+use std::process;
+use std::thread::sleep;
+use std::time::{Duration, Instant, SystemTime};
+
+fn usage() -> ! {
+    println!("usage: aoc watch <day> [--year <year>] [--interval <millis>]");
+    println!("       aoc verify [<day>] [--year <year>]");
+    println!("       aoc bench [<day>] [--year <year>] [--runs <n>]");
+    process::exit(1);
+}
+
+/// Parse and solve a single input file, turning every failure into a message
+fn solve(solution: &Solution, file: &PathBuf) -> Result<(Vec<String>, Duration), String> {
+    let input = read_to_string(file).map_err(|e| format!("cannot read file: {}", e))?;
+    let solve = solution.solve;
+    let start = Instant::now();
+    let res = panic::catch_unwind(|| solve(&input));
+    let elapsed = start.elapsed();
+    match res {
+        Ok(Ok(answers)) => Ok((answers, elapsed)),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("solver failed after {:?}", elapsed)),
+    }
+}
+
+/// Parse and solve a single input file, reporting answers, timing and
+/// failures without ever stopping the caller
+fn run(solution: &Solution, file: &PathBuf) {
+    match solve(solution, file) {
+        Ok((answers, elapsed)) => {
+            for (i, answer) in answers.iter().enumerate() {
+                println!("{} part {}: {}", file.display(), i + 1, answer);
+            }
+            println!("{} solved in {:?}", file.display(), elapsed);
+        }
+        Err(e) => println!("{}: {}", file.display(), e),
+    }
+}
+
+/// The registered solutions of a year, or only the one of some day
+fn selected(year: u16, day: Option<u8>) -> Vec<Solution> {
+    let res: Vec<Solution> = solutions()
+        .into_iter()
+        .filter(|s| s.year == year && day.is_none_or(|d| s.day == d))
+        .collect();
+    if res.is_empty() {
+        println!("no solution registered for {} {:?}", year, day);
+        process::exit(1);
+    }
+    res
+}
+
+/// Compare the answers on every input file with its expected answers,
+/// returning whether they all match
+fn verify(year: u16, day: Option<u8>) -> bool {
+    let mut ok = true;
+    for solution in selected(year, day) {
+        for file in input_files(year, solution.day) {
+            let (answers, elapsed) = match solve(&solution, &file) {
+                Ok(res) => res,
+                Err(e) => {
+                    println!("{}: {}", file.display(), e);
+                    ok = false;
+                    continue;
+                }
+            };
+            match expected_answers(&file) {
+                Some(expected) if expected == answers => {
+                    println!("{}: ok in {:?}", file.display(), elapsed)
+                }
+                Some(expected) => {
+                    println!(
+                        "{}: expected {:?}, got {:?}",
+                        file.display(),
+                        expected,
+                        answers
+                    );
+                    ok = false;
+                }
+                None => println!("{}: no expected answers, got {:?}", file.display(), answers),
+            }
+        }
+    }
+    ok
+}
+
+/// Solve every input file `runs` times, reporting the fastest and mean times
+fn bench(year: u16, day: Option<u8>, runs: u32) {
+    for solution in selected(year, day) {
+        for file in input_files(year, solution.day) {
+            let mut times = vec![];
+            for _ in 0..runs {
+                match solve(&solution, &file) {
+                    Ok((_, elapsed)) => times.push(elapsed),
+                    Err(e) => {
+                        println!("{}: {}", file.display(), e);
+                        break;
+                    }
+                }
+            }
+            if let Some(fastest) = times.iter().min() {
+                let mean = times.iter().sum::<Duration>() / times.len() as u32;
+                println!(
+                    "{}: fastest {:?}, mean {:?} over {} runs",
+                    file.display(),
+                    fastest,
+                    mean,
+                    times.len()
+                );
+            }
+        }
+    }
+}
+
+fn modification_times(files: &[PathBuf]) -> HashMap<PathBuf, SystemTime> {
+    files
+        .iter()
+        .filter_map(|f| {
+            metadata(f)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|t| (f.clone(), t))
+        })
+        .collect()
+}
+
+/// Poll the input files of some puzzle every `interval`, re-running the
+/// solution on any file which appeared or changed since last poll
+fn watch(year: u16, day: u8, interval: Duration) {
+    let solution = match find(year, day) {
+        Some(solution) => solution,
+        None => {
+            println!("no solution registered for {} day {}", year, day);
+            process::exit(1);
+        }
+    };
+    println!("watching {:?}", input_files(year, day));
+    let mut seen = HashMap::new();
+    loop {
+        let current = modification_times(&input_files(year, day));
+        let mut changed: Vec<&PathBuf> = current
+            .iter()
+            .filter(|(f, t)| seen.get(*f) != Some(*t))
+            .map(|(f, _)| f)
+            .collect();
+        changed.sort();
+        for file in changed {
+            run(&solution, file);
+        }
+        seen = current;
+        sleep(interval);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() < 2 {
+        usage();
+    }
+
+    // the day is optional for the commands running every registered day
+    let day = args
+        .get(2)
+        .filter(|a| !a.starts_with("--"))
+        .map(|d| d.parse::<u8>().unwrap_or_else(|_| usage()));
+    let option = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .and_then(|v| v.parse::<u64>().ok())
+    };
+    let year = option("--year").map_or_else(latest_year, |y| y as u16);
+    let interval = option("--interval").unwrap_or(500);
+    let runs = option("--runs").unwrap_or(10).max(1) as u32;
+
+    match args[1].as_str() {
+        "watch" => watch(
+            year,
+            day.unwrap_or_else(|| usage()),
+            Duration::from_millis(interval),
+        ),
+        "verify" => {
+            if !verify(year, day) {
+                process::exit(1);
+            }
+        }
+        "bench" => bench(year, day, runs),
+        _ => usage(),
+    }
+}
@@ -1,4 +1,6 @@
+use aoc2021::generators;
 use aoc2021::parser::E;
+use aoc2021::y2021::{self, naive};
 use core::u64::MAX;
 use nom::bytes::complete::tag;
 use nom::character::complete::anychar;
@@ -11,6 +13,8 @@ use std::process;
 
 type Insertion = HashMap<(char, char), char>;
 
+const FUZZ_STEPS: u8 = 10;
+
 fn update<K: Eq + Hash>(map: &mut HashMap<K, u64>, p: K, k: u64) {
     match map.get_mut(&p) {
         Some(n) => *n += k,
@@ -51,15 +55,17 @@ fn solve(instructions: &(String, Insertion), steps: u8) -> HashMap<(char, char),
     res
 }
 
-fn compute_number(result: &HashMap<(char, char), u64>) -> u64 {
+/// Count elements as the first one of each pair, plus the last element of the
+/// template which never moves
+fn compute_number(result: &HashMap<(char, char), u64>, last: char) -> u64 {
     let mut chars_count: HashMap<char, u64> = HashMap::new();
     for (p, v) in result.iter() {
         update(&mut chars_count, p.0, *v);
-        update(&mut chars_count, p.1, *v);
     }
+    update(&mut chars_count, last, 1);
 
     let (mut max, mut min) = (0, MAX);
-    chars_count.iter().map(|(_, v)| v / 2).for_each(|v| {
+    chars_count.iter().map(|(_, v)| *v).for_each(|v| {
         if v > max {
             max = v;
         }
@@ -68,7 +74,7 @@ fn compute_number(result: &HashMap<(char, char), u64>) -> u64 {
         }
     });
 
-    max - min + 1
+    max - min
 }
 
 fn parse_instructions(lines: &Vec<&str>) -> (String, Insertion) {
@@ -89,6 +95,24 @@ fn parse_instructions(lines: &Vec<&str>) -> (String, Insertion) {
     (template.to_string(), inserts)
 }
 
+/// Compare pair counting against growing the actual polymer on random inputs
+fn fuzz(args: &[String]) {
+    let (seeds, size) = generators::fuzz_args(args, 10);
+    let fast = |input: &str| {
+        let instructions = parse_instructions(&input.split("\n").collect());
+        let last = instructions.0.chars().last().unwrap();
+        compute_number(&solve(&instructions, FUZZ_STEPS), last)
+    };
+    let naive = |input: &str| naive::polymer(input, FUZZ_STEPS);
+    generators::report(generators::fuzz(
+        seeds,
+        size,
+        y2021::generators::polymer,
+        fast,
+        naive,
+    ));
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
@@ -96,10 +120,16 @@ fn main() {
         process::exit(1);
     }
 
+    if args[1] == "fuzz" {
+        fuzz(&args);
+        return;
+    }
+
     if let Ok(input) = read_to_string(&args[1]) {
         let instructions = parse_instructions(&input.split("\n").collect());
         let solution = solve(&instructions, 40);
-        println!("{}", compute_number(&solution));
+        let last = instructions.0.chars().last().unwrap();
+        println!("{}", compute_number(&solution, last));
     } else {
         println!("fail to parse {}", args[1]);
     }
@@ -118,7 +148,7 @@ mod tests {
         ];
 
         let insts = parse_instructions(&input);
-        let res = compute_number(&solve(&insts, 10));
+        let res = compute_number(&solve(&insts, 10), 'B');
 
         assert_eq!(res, 1588);
     }
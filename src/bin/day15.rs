@@ -1,7 +1,6 @@
 use aoc2021::nums::all_neighbours;
 use aoc2021::nums::neighbours;
 use aoc2021::parser::parse_digits;
-use aoc2021::vents::Pos;
 use core::u64::MAX;
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
@@ -26,6 +25,12 @@ fn print_path(nums: &Vec<Vec<u64>>) {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+struct Pos {
+    x: usize,
+    y: usize,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq)]
 struct State {
     cost: u64,
@@ -1,5 +1,6 @@
 use crate::bits::complete::tag;
 use crate::bits::complete::take;
+use aoc2021::parallel::{jobs_from_args, par_map};
 use aoc2021::parser::Ebits;
 use hex;
 use nom::bits;
@@ -46,20 +47,21 @@ fn hit(ranges: ((i32, i32), (i32, i32)), velocity: (i32, i32)) -> Option<i32> {
     None
 }
 
-fn solve(ranges: ((i32, i32), (i32, i32))) -> Vec<(i32, (i32, i32))> {
-    let mut solutions = vec![];
-    for x in 1..400 {
-        for y in -75..1000 {
-            if let Some(maxy) = hit(ranges, (x, y)) {
-                solutions.push((maxy, (x, y)));
-            }
-        }
-    }
-    solutions
+fn solve(ranges: ((i32, i32), (i32, i32)), jobs: usize) -> Vec<(i32, (i32, i32))> {
+    let xs: Vec<i32> = (1..400).collect();
+    par_map(&xs, jobs, |&x| {
+        (-75..1000)
+            .filter_map(|y| hit(ranges, (x, y)).map(|maxy| (maxy, (x, y))))
+            .collect::<Vec<_>>()
+    })
+    .into_iter()
+    .flatten()
+    .collect()
 }
 
 fn main() {
-    let solution = solve(((253, 280), (-73, -46)));
+    let args: Vec<String> = env::args().collect();
+    let solution = solve(((253, 280), (-73, -46)), jobs_from_args(&args));
     println!("{:?}", solution.len());
 }
 
@@ -71,8 +73,15 @@ mod tests {
     fn can_parse_literal_value() {
         let input = ((20, 30), (-10, 5));
 
-        let res = solve(input);
+        let res = solve(input, 1);
 
         assert_eq!(res.len(), 112);
     }
+
+    #[test]
+    fn parallel_solve_gives_same_solutions() {
+        let input = ((20, 30), (-10, 5));
+
+        assert_eq!(solve(input, 4), solve(input, 1));
+    }
 }
@@ -1,3 +1,4 @@
+use aoc2021::parallel::{jobs_from_args, par_map};
 use aoc2021::parser::num;
 use nom::branch::alt;
 use nom::character::complete::char;
@@ -173,6 +174,25 @@ fn magnitude(sn: SN) -> u64 {
     }
 }
 
+/// Compute the largest magnitude of the sum of any 2 different numbers,
+/// splitting the work over `jobs` threads
+fn max_magnitude(sns: &Vec<SN>, jobs: usize) -> u64 {
+    par_map(sns, jobs, |s1| {
+        let mut max = MIN;
+        for s2 in sns.iter() {
+            if *s1 != *s2 {
+                let mag = magnitude(reduce(add(s1.clone(), s2.clone())));
+                if mag > max {
+                    max = mag;
+                }
+            }
+        }
+        max
+    })
+    .into_iter()
+    .fold(MIN, |m, mag| m.max(mag))
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
@@ -183,20 +203,7 @@ fn main() {
     if let Ok(input) = read_to_string(&args[1]) {
         let nums: Vec<&str> = input.split("\n").filter(|s| !s.is_empty()).collect();
         let sns: Vec<SN> = nums.iter().map(|s| parse_sn(s).unwrap().1).collect();
-        let other = sns.clone();
-        let mut max = MIN;
-        for s1 in sns.iter() {
-            for s2 in other.iter() {
-                let a = s1.clone();
-                let b = s2.clone();
-                if *s1 != *s2 {
-                    let mag = magnitude(reduce(add(a, b)));
-                    if mag > max {
-                        max = mag;
-                    }
-                }
-            }
-        }
+        let max = max_magnitude(&sns, jobs_from_args(&args));
         println!("max magnitude: {}", max);
     } else {
         println!("fail to parse {}", args[1]);
@@ -335,4 +342,24 @@ mod tests {
         assert_eq!(res, expected);
         assert_eq!(magnitude(res), 4140);
     }
+    #[test]
+    fn can_compute_max_magnitude_in_parallel() {
+        let input = vec![
+            "[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]",
+            "[[[5,[2,8]],4],[5,[[9,9],0]]]",
+            "[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]",
+            "[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]",
+            "[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]",
+            "[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]",
+            "[[[[5,4],[7,7]],8],[[8,3],8]]",
+            "[[9,3],[[9,9],[6,[4,9]]]]",
+            "[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]",
+            "[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]",
+        ];
+
+        let sns: Vec<SN> = input.iter().map(|s| parse_sn(s).unwrap().1).collect();
+
+        assert_eq!(max_magnitude(&sns, 1), 3993);
+        assert_eq!(max_magnitude(&sns, 4), 3993);
+    }
 }
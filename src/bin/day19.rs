@@ -1,6 +1,10 @@
+use aoc2021::budget::{Budget, Exhausted};
 use aoc2021::geometry::*;
+use aoc2021::parallel::{jobs_from_args, par_map};
 use std::collections::HashSet;
 use std::collections::VecDeque;
+use std::env;
+use std::process;
 use std::u64::MIN;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -835,32 +839,163 @@ fn max_distance(pos: &Vec<Point>) -> u64 {
     maxd
 }
 
-fn find_all_beacons(scanners: &Vec<Scanner>) -> (Vec<Point>, HashSet<Point>) {
+/// Why the beacons of all the scanners could not be found
+#[derive(Debug, PartialEq, Eq)]
+enum BeaconError {
+    Stopped(Exhausted),
+    /// Ids of the scanners overlapping none of the merged ones
+    Unmatched(Vec<u8>),
+}
+
+impl From<Exhausted> for BeaconError {
+    fn from(reason: Exhausted) -> BeaconError {
+        BeaconError::Stopped(reason)
+    }
+}
+
+/// Merge all scanners into the cloud of points seen by the first one, taking
+/// them from a queue and putting back at the end those which do not match.
+/// The next `jobs` scanners of the queue are tried at once, and the results
+/// up to the first match are used as if they were tried one at a time, so
+/// the result does not depend on `jobs`.
+fn find_all_beacons(
+    scanners: &[Scanner],
+    budget: &mut Budget,
+    jobs: usize,
+) -> Result<(Vec<Point>, HashSet<Point>), BeaconError> {
     let mut points = HashSet::new();
     let mut origins = vec![];
     points.extend(scanners[0].beacons.clone());
     let mut to_scan = VecDeque::from(scanners[1..].to_vec());
+    // scanners tried in a row without matching since the last merge
+    let mut failed = 0;
 
     while !to_scan.is_empty() {
-        println!("remaining {}", to_scan.len());
-        let sc = to_scan.pop_front().unwrap();
-        let beacons = sc.beacons.clone().into_iter().collect();
-        if let Some((origin, matched_points)) = matching_vectors(&points, &beacons) {
-            origins.push(origin);
-            points.extend(matched_points);
-        } else {
-            to_scan.push_back(sc);
+        if failed == to_scan.len() {
+            return Err(BeaconError::Unmatched(
+                to_scan.iter().map(|sc| sc.id).collect(),
+            ));
+        }
+        let tried = jobs.max(1).min(to_scan.len() - failed);
+        let matches = par_map(&to_scan.make_contiguous()[..tried], jobs, |sc| {
+            let beacons = sc.beacons.clone().into_iter().collect();
+            matching_vectors(&points, &beacons)
+        });
+        for matched in matches {
+            budget.best(points.len() as i64);
+            budget.tick(to_scan.len())?;
+            let sc = to_scan.pop_front().unwrap();
+            if let Some((origin, matched_points)) = matched {
+                origins.push(origin);
+                points.extend(matched_points);
+                failed = 0;
+                // the following scanners must be tried against the new points
+                break;
+            } else {
+                to_scan.push_back(sc);
+                failed += 1;
+            }
         }
     }
 
-    (origins, points)
+    Ok((origins, points))
 }
 
 fn main() {
-    let (scans, beacons) = find_all_beacons(&scanners());
-    println!(
-        "max distance {}, num beacons {}",
-        max_distance(&scans),
-        beacons.len()
-    );
+    let args: Vec<String> = env::args().collect();
+    let mut budget = match Budget::from_args(&args) {
+        Ok(budget) => budget,
+        Err(e) => {
+            println!("{}", e);
+            process::exit(1);
+        }
+    };
+    match find_all_beacons(&scanners(), &mut budget, jobs_from_args(&args)) {
+        Ok((scans, beacons)) => println!(
+            "max distance {}, num beacons {}",
+            max_distance(&scans),
+            beacons.len()
+        ),
+        Err(BeaconError::Stopped(reason)) => {
+            println!("search stopped ({:?}): {:?}", reason, budget.progress())
+        }
+        Err(BeaconError::Unmatched(ids)) => println!("scanners {:?} match no other", ids),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aoc2021::generators::Rng;
+    use std::collections::VecDeque;
+
+    /// The merge as it was before it ran on several threads
+    fn sequential_beacons(scanners: &[Scanner]) -> (Vec<Point>, HashSet<Point>) {
+        let mut points = HashSet::new();
+        let mut origins = vec![];
+        points.extend(scanners[0].beacons.clone());
+        let mut to_scan = VecDeque::from(scanners[1..].to_vec());
+
+        while !to_scan.is_empty() {
+            let sc = to_scan.pop_front().unwrap();
+            let beacons = sc.beacons.clone().into_iter().collect();
+            if let Some((origin, matched_points)) = matching_vectors(&points, &beacons) {
+                origins.push(origin);
+                points.extend(matched_points);
+            } else {
+                to_scan.push_back(sc);
+            }
+        }
+        (origins, points)
+    }
+
+    /// Scanners seeing overlapping slices of random beacons, each rotated
+    /// and moved, in the given order of slices
+    fn sample(slices: &[usize]) -> Vec<Scanner> {
+        let mut rng = Rng::new(19);
+        let beacons: Vec<Point> = (0..50)
+            .map(|_| [0; 3].map(|_| rng.range(-1000, 1000)))
+            .collect();
+        slices
+            .iter()
+            .enumerate()
+            .map(|(id, slice)| {
+                let rotation = ALL_ROTATIONS[rng.below(24) as usize];
+                let origin = [0; 3].map(|_| rng.range(-100, 100));
+                let seen = &beacons[slice * 6..slice * 6 + 18];
+                Scanner {
+                    id: id as u8,
+                    beacons: seen
+                        .iter()
+                        .map(|b| plus(rotation.rotate(*b), origin))
+                        .collect(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_beacons_whatever_the_jobs() {
+        // neighbouring slices share 12 beacons, so scanners only match once
+        // the previous slice is merged
+        let scanners = sample(&[2, 4, 0, 3, 1]);
+        let expected = sequential_beacons(&scanners);
+        assert_eq!(expected.1.len(), 4 * 6 + 18);
+
+        for jobs in [1, 4] {
+            let res = find_all_beacons(&scanners, &mut Budget::unlimited(), jobs);
+            assert_eq!(res, Ok(expected.clone()));
+        }
+    }
+
+    #[test]
+    fn report_unmatched_scanners() {
+        // slices 0 and 2 share too few beacons
+        let scanners = sample(&[0, 2, 1]);
+        for jobs in [1, 4] {
+            let res = find_all_beacons(&scanners[..2], &mut Budget::unlimited(), jobs);
+            assert_eq!(res, Err(BeaconError::Unmatched(vec![1])));
+        }
+        assert!(find_all_beacons(&scanners, &mut Budget::unlimited(), 4).is_ok());
+    }
 }
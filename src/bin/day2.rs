@@ -1,17 +1,35 @@
 use aoc2021::files::read_lines;
-use aoc2021::parser::{parse_move, Move};
+use aoc2021::parser::parse_move;
+use aoc2021::y2021::submarine::{Aim, Submarine};
+use std::env;
+use std::fs::File;
+use std::process;
 
 fn main() {
-    if let Ok(lines) = read_lines("./day2/input.txt") {
+    let args: Vec<String> = env::args().collect();
+    if let Ok(lines) = read_lines("./inputs/2021/2/input.txt") {
         let moves = lines.map(|line| parse_move(&line.unwrap()).unwrap());
 
-        let apply_move = |(x, y, a), (mv, dy)| match mv {
-            Move::Up => (x, y, a - dy),
-            Move::Down => (x, y, a + dy),
-            Move::Forward => (x + dy, y + (a * dy), a),
+        let mut submarine = Submarine::new(Aim);
+        if args.iter().any(|a| a == "--strict") {
+            submarine = submarine.strict();
+        }
+        let final_pos = match submarine.run_all(moves) {
+            Ok(state) => state,
+            Err(e) => {
+                println!("{}", e);
+                process::exit(1);
+            }
         };
+        println!("{}", final_pos.position * final_pos.depth);
 
-        let final_pos = moves.fold((0, 0, 0), apply_move);
-        println!("{}", final_pos.0 * final_pos.1);
+        // optionally export the whole trajectory
+        if let Some(i) = args.iter().position(|a| a == "--csv") {
+            let file = args.get(i + 1).and_then(|f| File::create(f).ok());
+            match file {
+                Some(file) => submarine.write_csv(file).unwrap(),
+                None => println!("cannot write trajectory"),
+            }
+        }
     }
 }
@@ -1,4 +1,6 @@
 use crate::Pos::*;
+use aoc2021::generators;
+use aoc2021::y2021::{self, naive};
 use std::collections::BTreeMap;
 use std::collections::HashSet;
 use std::env;
@@ -65,30 +67,6 @@ fn make_treemap(
     (btx, bty, btz)
 }
 
-fn make_treemap(
-    bounds: &(Vec<i64>, Vec<i64>, Vec<i64>),
-) -> (
-    BTreeMap<i64, usize>,
-    BTreeMap<i64, usize>,
-    BTreeMap<i64, usize>,
-) {
-    let (bx, by, bz) = bounds;
-    let mut btx = BTreeMap::new();
-    for (i, v) in bx.iter().enumerate() {
-        btx.insert(*v, i);
-    }
-    let mut bty = BTreeMap::new();
-    for (i, v) in by.iter().enumerate() {
-        bty.insert(*v, i);
-    }
-    let mut btz = BTreeMap::new();
-    for (i, v) in bz.iter().enumerate() {
-        btz.insert(*v, i);
-    }
-
-    (btx, bty, btz)
-}
-
 fn on_cubes(bounds: &(Vec<i64>, Vec<i64>, Vec<i64>), steps: &Vec<Cuboid>) -> i64 {
     let (bvx, bvy, bvz) = bounds;
     let (bx, by, bz) = make_treemap(bounds);
@@ -146,6 +124,27 @@ fn make_bounds(cuboids: &Vec<Cuboid>) -> (Vec<i64>, Vec<i64>, Vec<i64>) {
     (vvx, vvy, vvz)
 }
 
+/// Compare coordinates compression against lighting individual cubes on
+/// random inputs
+fn fuzz(args: &[String]) {
+    let (seeds, size) = generators::fuzz_args(args, 8);
+    let fast = |input: &str| {
+        let steps: Vec<Cuboid> = input
+            .split("\n")
+            .filter(|s| !s.is_empty())
+            .map(parse_cuboid_step)
+            .collect();
+        on_cubes(&make_bounds(&steps), &steps)
+    };
+    generators::report(generators::fuzz(
+        seeds,
+        size,
+        y2021::generators::reactor,
+        fast,
+        naive::reactor,
+    ));
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
@@ -153,6 +152,11 @@ fn main() {
         process::exit(1);
     }
 
+    if args[1] == "fuzz" {
+        fuzz(&args);
+        return;
+    }
+
     if let Ok(input) = read_to_string(&args[1]) {
         let nums: Vec<&str> = input.split("\n").filter(|s| !s.is_empty()).collect();
         let cuboid_steps: Vec<Cuboid> = nums.iter().map(|s| parse_cuboid_step(s)).collect();
@@ -1,5 +1,6 @@
 use crate::Amphipod::*;
 use crate::MoveType::*;
+use aoc2021::budget::{Budget, Exhausted};
 use core::u32::MAX;
 use num::abs;
 use std::cmp::Ordering;
@@ -1089,7 +1090,8 @@ fn compute_min_steps(
     path: &mut Vec<Pos>,
     min_e: &mut u32,
     energy: u32,
-) {
+    budget: &mut Budget,
+) -> Result<(), Exhausted> {
     let mut heap = BinaryHeap::new();
     let mut visited = vec![];
     let mut open: HashMap<u64, u32> = HashMap::new();
@@ -1100,21 +1102,15 @@ fn compute_min_steps(
         prev: *cur_pos,
     });
     open.insert(encode(&cur_pos), 0);
-    while let Some(Node { val, e, pos, prev }) = heap.pop() {
+    while let Some(Node { e, pos, .. }) = heap.pop() {
         let code = encode(&pos);
         if is_winning(&pos) {
             *min_e = e;
-            return;
+            budget.best(e as i64);
+            return Ok(());
         }
+        budget.tick(heap.len())?;
         let next_moves = compute_moves(all_paths, &pos);
-        println!(
-            "checking {} {} {} {:?} {}",
-            code,
-            e,
-            val,
-            pos,
-            next_moves.len()
-        );
         for (nm, ne) in next_moves {
             let ncode = encode(&nm);
             if !visited.contains(&ncode) {
@@ -1141,6 +1137,7 @@ fn compute_min_steps(
         }
         visited.push(code);
     }
+    Ok(())
 }
 
 fn main() {
@@ -1156,9 +1153,19 @@ fn main() {
     let puzzle: [Amphipod; 27] = [
         X, X, X, X, X, X, X, X, X, X, X, D, D, D, C, B, C, B, A, D, B, A, A, B, A, C, C,
     ];
+    let args: Vec<String> = env::args().collect();
+    let mut budget = match Budget::from_args(&args) {
+        Ok(budget) => budget,
+        Err(e) => {
+            println!("{}", e);
+            process::exit(1);
+        }
+    };
     let paths = compute_all_paths();
     let mut path = vec![];
     let mut min_e = MAX;
-    compute_min_steps(&paths, &puzzle, &mut path, &mut min_e, 0);
-    println!("min energy: {}", min_e);
+    match compute_min_steps(&paths, &puzzle, &mut path, &mut min_e, 0, &mut budget) {
+        Ok(()) => println!("min energy: {}", min_e),
+        Err(reason) => println!("search stopped ({:?}): {:?}", reason, budget.progress()),
+    }
 }
@@ -3,6 +3,9 @@ use crate::Inst::*;
 use crate::Op::*;
 use crate::Operand::*;
 use crate::AST::*;
+use aoc2021::budget::Budget;
+use aoc2021::generators;
+use aoc2021::y2021::{self, naive};
 use core::i64::MAX;
 use core::i64::MIN;
 use num::pow;
@@ -11,6 +14,7 @@ use std::collections::HashSet;
 use std::env;
 use std::fmt;
 use std::fmt::Display;
+use std::process;
 use z3::ast::{Ast, Bool};
 use z3::*;
 #[macro_use]
@@ -768,17 +772,56 @@ fn solve(
     }
 }
 
+/// Constraints on the digits of the actual puzzle input, found by exploration
+fn assert_hints<'ctx>(ctx: &'ctx Context, solver: &Solver<'ctx>) {
+    // smallest so far 62911981716511
+    //                 62911941716111
+    // there's no solution starting with 5
+    //solver.push();
+    let c0 = ast::Int::new_const(ctx, "I_0")._eq(&ast::Int::from_u64(ctx, 6));
+    let c1 = ast::Int::new_const(ctx, "I_1")._eq(&ast::Int::from_u64(ctx, 2));
+    let c2 = ast::Int::new_const(ctx, "I_2")._eq(&ast::Int::from_u64(ctx, 9));
+    let c3 = ast::Int::new_const(ctx, "I_3")._eq(&ast::Int::from_u64(ctx, 1));
+    let c4 = ast::Int::new_const(ctx, "I_4")._eq(&ast::Int::from_u64(ctx, 1));
+    let c5 = ast::Int::new_const(ctx, "I_5")._eq(&ast::Int::from_u64(ctx, 9));
+    let c6 = ast::Int::new_const(ctx, "I_6").lt(&ast::Int::from_u64(ctx, 8));
+    // let c7 = ast::Int::new_const(ctx, "I_7")._eq(&ast::Int::from_u64(ctx, 1));
+    // let c8 = ast::Int::new_const(ctx, "I_8")._eq(&ast::Int::from_u64(ctx, 7));
+    // let c9 = ast::Int::new_const(ctx, "I_9")._eq(&ast::Int::from_u64(ctx, 1));
+    // let c10 = ast::Int::new_const(ctx, "I_10")._eq(&ast::Int::from_u64(ctx, 6));
+    // let c11 = ast::Int::new_const(ctx, "I_11")._eq(&ast::Int::from_u64(ctx, 5));
+    // let c12 = ast::Int::new_const(ctx, "I_12")._eq(&ast::Int::from_u64(ctx, 1));
+    // let c13 = ast::Int::new_const(ctx, "I_13")._eq(&ast::Int::from_u64(ctx, 1));
+    solver.assert(&c0);
+    solver.assert(&c1);
+    solver.assert(&c2);
+    solver.assert(&c3);
+    solver.assert(&c4);
+    solver.assert(&c5);
+    solver.assert(&c6);
+    // solver.assert(&c7);
+    // solver.assert(&c8);
+    // solver.assert(&c9);
+    // solver.assert(&c10);
+    // solver.assert(&c11);
+    // solver.assert(&c12);
+    // solver.assert(&c13);
+}
+
 /// Transform a sequence of expressions for each stage of the ALU into
-/// Z3 equations and solve them
-fn solve_z3(eqs: &Vec<AST>) -> Vec<Vec<u8>> {
+/// Z3 equations and solve them, until no smaller solution exists or the
+/// budget is exhausted. When `hinted`, constraints found by exploring
+/// the actual puzzle input are added to speed up the search.
+fn solve_z3(eqs: &Vec<AST>, hinted: bool, budget: &mut Budget) -> Vec<Vec<u8>> {
     let cfg = Config::new();
     let ctx = Context::new(&cfg);
     let solver = Solver::new(&ctx);
     let zero = ast::Int::from_i64(&ctx, 0);
     let nine = ast::Int::from_i64(&ctx, 9);
+    let n = eqs.len();
 
     // all I_X are between 1 and 9
-    for i in 0..14 {
+    for i in 0..n {
         let index = ast::Int::new_const(&ctx, format!("I_{}", i));
         solver.assert(&index.le(&nine));
         solver.assert(&index.gt(&zero));
@@ -786,7 +829,7 @@ fn solve_z3(eqs: &Vec<AST>) -> Vec<Vec<u8>> {
 
     for (i, eq) in eqs.iter().enumerate() {
         let expr = to_z3(eq, i as u8, &ctx);
-        if i == 13 {
+        if i == n - 1 {
             let z3eq = expr._eq(&zero);
             println!("eq({}) : {:?}", i, z3eq);
             solver.assert(&expr._eq(&zero));
@@ -800,45 +843,16 @@ fn solve_z3(eqs: &Vec<AST>) -> Vec<Vec<u8>> {
     // Z initial value is 0
     solver.assert(&ast::Int::new_const(&ctx, "Z_0")._eq(&zero));
 
-    // smallest so far 62911981716511
-    //                 62911941716111
-    // there's no solution starting with 5
-    //solver.push();
-    let c0 = ast::Int::new_const(&ctx, "I_0")._eq(&ast::Int::from_u64(&ctx, 6));
-    let c1 = ast::Int::new_const(&ctx, "I_1")._eq(&ast::Int::from_u64(&ctx, 2));
-    let c2 = ast::Int::new_const(&ctx, "I_2")._eq(&ast::Int::from_u64(&ctx, 9));
-    let c3 = ast::Int::new_const(&ctx, "I_3")._eq(&ast::Int::from_u64(&ctx, 1));
-    let c4 = ast::Int::new_const(&ctx, "I_4")._eq(&ast::Int::from_u64(&ctx, 1));
-    let c5 = ast::Int::new_const(&ctx, "I_5")._eq(&ast::Int::from_u64(&ctx, 9));
-    let c6 = ast::Int::new_const(&ctx, "I_6").lt(&ast::Int::from_u64(&ctx, 8));
-    // let c7 = ast::Int::new_const(&ctx, "I_7")._eq(&ast::Int::from_u64(&ctx, 1));
-    // let c8 = ast::Int::new_const(&ctx, "I_8")._eq(&ast::Int::from_u64(&ctx, 7));
-    // let c9 = ast::Int::new_const(&ctx, "I_9")._eq(&ast::Int::from_u64(&ctx, 1));
-    // let c10 = ast::Int::new_const(&ctx, "I_10")._eq(&ast::Int::from_u64(&ctx, 6));
-    // let c11 = ast::Int::new_const(&ctx, "I_11")._eq(&ast::Int::from_u64(&ctx, 5));
-    // let c12 = ast::Int::new_const(&ctx, "I_12")._eq(&ast::Int::from_u64(&ctx, 1));
-    // let c13 = ast::Int::new_const(&ctx, "I_13")._eq(&ast::Int::from_u64(&ctx, 1));
-    solver.assert(&c0);
-    solver.assert(&c1);
-    solver.assert(&c2);
-    solver.assert(&c3);
-    solver.assert(&c4);
-    solver.assert(&c5);
-    solver.assert(&c6);
-    // solver.assert(&c7);
-    // solver.assert(&c8);
-    // solver.assert(&c9);
-    // solver.assert(&c10);
-    // solver.assert(&c11);
-    // solver.assert(&c12);
-    // solver.assert(&c13);
+    if hinted {
+        assert_hints(&ctx, &solver);
+    }
 
     let mut res = vec![];
 
-    while solver.check() == SatResult::Sat {
+    while budget.check().is_ok() && solver.check() == SatResult::Sat {
         let model = solver.get_model().unwrap();
         let mut sol = vec![];
-        for i in 0..14 {
+        for i in 0..n {
             let index = ast::Int::new_const(&ctx, format!("I_{}", i));
             let v = model.eval(&index, true).unwrap().as_u64().unwrap();
             sol.push(v as u8);
@@ -846,21 +860,90 @@ fn solve_z3(eqs: &Vec<AST>) -> Vec<Vec<u8>> {
         println!("solution {:?}", sol);
         // add constraint to find larger solution to solver
         //        solver.pop(1);
-        let ctr = (0..14).fold((0, ast::Int::from_i64(&ctx, 0)), |(v, e), i| {
-            let exp = pow(10u64, 13 - i);
+        let ctr = (0..n).fold((0, ast::Int::from_i64(&ctx, 0)), |(v, e), i| {
+            let exp = pow(10u64, n - 1 - i);
             let nv = v + exp * sol[i] as u64;
             let index = ast::Int::new_const(&ctx, format!("I_{}", i));
             (nv, (e + index * ast::Int::from_u64(&ctx, exp)))
         });
         //        solver.push();
         let nequation = ctr.1.lt(&ast::Int::from_u64(&ctx, ctr.0));
-        println!("eq: {:?}", nequation);
         solver.assert(&nequation);
         res.push(sol);
+        budget.best(ctr.0 as i64);
+        if let Err(reason) = budget.tick(res.len()) {
+            println!("search stopped ({:?}): {:?}", reason, budget.progress());
+        }
     }
     res
 }
 
+fn parse_addr(s: &str) -> Addr {
+    match s {
+        "x" => X,
+        "y" => Y,
+        "z" => Z,
+        _ => W,
+    }
+}
+
+/// Parse an ALU program, numbering inputs in order of appearance
+fn parse_program(input: &str) -> Vec<Inst> {
+    let mut inputs = 0;
+    let mut prog = vec![];
+    for line in input.split("\n").filter(|s| !s.is_empty()) {
+        let parts: Vec<&str> = line.split(' ').collect();
+        let addr = parse_addr(parts[1]);
+        if parts[0] == "inp" {
+            prog.push(Inp(addr, I(inputs)));
+            inputs += 1;
+            continue;
+        }
+        let opr = parts[2]
+            .parse::<i64>()
+            .map_or_else(|_| A(parse_addr(parts[2])), V);
+        prog.push(match parts[0] {
+            "add" => Add(addr, opr),
+            "mul" => Mul(addr, opr),
+            "div" => Div(addr, opr),
+            "mod" => Mod(addr, opr),
+            _ => Eql(addr, opr),
+        });
+    }
+    prog
+}
+
+/// Find the smallest model number accepted by some program through its
+/// symbolic equations and z3
+fn smallest_model_number(input: &str) -> Option<u64> {
+    let init = AbsALU {
+        x: Leaf(A(X)),
+        y: Leaf(A(Y)),
+        z: Leaf(A(Z)),
+        w: Leaf(A(W)),
+    };
+    let prog = parse_program(input);
+    let zs: Vec<AST> = prog
+        .chunks(18)
+        .map(|block| abstract_interpret(&block.to_vec(), &init).z)
+        .collect();
+    solve_z3(&zs, false, &mut Budget::unlimited())
+        .pop()
+        .map(|sol| sol.iter().fold(0, |n, d| n * 10 + *d as u64))
+}
+
+/// Compare the z3 path against trying every model number on random programs
+fn fuzz(args: &[String]) {
+    let (seeds, size) = generators::fuzz_args(args, 4);
+    generators::report(generators::fuzz(
+        seeds,
+        size,
+        y2021::generators::monad,
+        smallest_model_number,
+        naive::monad,
+    ));
+}
+
 fn main() {
     let init = AbsALU {
         x: Leaf(A(X)),
@@ -870,6 +953,11 @@ fn main() {
     };
     let args: Vec<String> = env::args().collect();
 
+    if args.len() > 1 && args[1] == "fuzz" {
+        fuzz(&args);
+        return;
+    }
+
     let lb = args[1].parse::<usize>().unwrap();
     let ub = args[2].parse::<usize>().unwrap();
     let mut zs = vec![];
@@ -884,7 +972,14 @@ fn main() {
 
     // solve(&mut zs, &mut cache, 0, 0, &mut result);
 
-    let res = solve_z3(&zs);
+    let mut budget = match Budget::from_args(&args[3..]) {
+        Ok(budget) => budget,
+        Err(e) => {
+            println!("{}", e);
+            process::exit(1);
+        }
+    };
+    let res = solve_z3(&zs, true, &mut budget);
 
     println!("result: {:?}", res);
     // verify result
@@ -1001,6 +1096,21 @@ mod tests {
         assert!(xv + 2 > 7);
     }
 
+    #[test]
+    fn parse_program_matches_hardcoded_program() {
+        let input = "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z 1\nadd x 12\neql x w\neql x 0\nmul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y 6\nmul y x\nadd z y";
+
+        assert_eq!(parse_program(input), PROGRAM[0..18].to_vec());
+    }
+
+    #[test]
+    fn z3_path_matches_naive_search_on_generated_programs() {
+        for seed in 0..5 {
+            let input = y2021::generators::monad(&mut generators::Rng::new(seed), 4);
+            assert_eq!(smallest_model_number(&input), naive::monad(&input));
+        }
+    }
+
     #[test]
     fn convert_ast_to_z3() {
         let abs_init = AbsALU {
@@ -1,7 +1,32 @@
-use aoc2021::bingo::{parse, play, sum_undrawn};
+use aoc2021::budget::Budget;
+use aoc2021::parallel::jobs_from_args;
+use aoc2021::y2021::bingo::{events, play, try_parse_bingo, Pattern, TieBreak, Win};
+use aoc2021::y2021::bingo_odds::simulate;
+use aoc2021::y2021::bingo_search::{search, Goal, Outcome};
 use std::env;
+use std::fs::read_to_string;
 use std::process;
 
+fn option(args: &[String], name: &str) -> Option<u64> {
+    args.iter()
+        .position(|a| a == name)
+        .and_then(|i| args.get(i + 1))
+        .and_then(|v| v.parse::<u64>().ok())
+}
+
+/// Print the winners of the same draw, if there are several of them
+fn report_tie(which: &str, winners: &[Win]) {
+    if winners.len() > 1 {
+        let boards: Vec<String> = winners.iter().map(|w| w.board.to_string()).collect();
+        println!(
+            "{} winners tied on draw {}: boards {}",
+            which,
+            winners[0].turn,
+            boards.join(", ")
+        );
+    }
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
@@ -9,12 +34,95 @@ fn main() {
         process::exit(1);
     }
 
-    if let Some(mut bingo) = parse(&args[1]) {
-        if let Some((winning_board, last_drawn_number)) = play(&mut bingo) {
-            let sum_of_undrawn = sum_undrawn(&winning_board);
-            println!("{}", sum_of_undrawn * last_drawn_number);
+    // eg. --patterns rows,columns,diagonals,corners,blackout,mask:10001/00000/00100/00000/10001
+    let patterns = match args.iter().position(|a| a == "--patterns") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|ps| {
+                ps.split(',')
+                    .map(Pattern::parse)
+                    .collect::<Option<Vec<_>>>()
+            })
+            .unwrap_or_else(|| {
+                println!("invalid patterns");
+                process::exit(1);
+            }),
+        None => Pattern::standard(),
+    };
+
+    // one of board (default), highest or lowest
+    let tie_break = match args.iter().position(|a| a == "--tie-break") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|t| TieBreak::parse(t))
+            .unwrap_or_else(|| {
+                println!("invalid tie-break policy");
+                process::exit(1);
+            }),
+        None => TieBreak::BoardOrder,
+    };
+
+    let input = read_to_string(&args[1]).unwrap_or_default();
+    let lines: Vec<&str> = input.split('\n').collect();
+    match try_parse_bingo(&lines) {
+        Ok(bingo) => {
+            let bingo = bingo.with_patterns(patterns).with_tie_break(tie_break);
+            for board in bingo.unwinnable() {
+                println!("board {} can never win", board);
+            }
+            if args.iter().any(|a| a == "--events") {
+                for event in events(&bingo) {
+                    println!("{:?}", event);
+                }
+            }
+            // eg. --simulate 10000 [--seed 42] [--jobs 4]
+            if let Some(trials) = option(&args, "--simulate") {
+                let seed = option(&args, "--seed").unwrap_or(0);
+                let stats = simulate(&bingo, trials, seed, jobs_from_args(&args));
+                println!("board,first,last,expected turn,mean score");
+                for s in stats {
+                    println!(
+                        "{},{:.4},{:.4},{:.2},{:.1}",
+                        s.board,
+                        s.first_probability().unwrap_or(f64::NAN),
+                        s.last_probability().unwrap_or(f64::NAN),
+                        s.expected_turn().unwrap_or(f64::NAN),
+                        s.mean_score().unwrap_or(f64::NAN)
+                    );
+                }
+                return;
+            }
+            // eg. --win-last 2 [--time-limit 10] [--max-iterations 1000000]
+            let goal = option(&args, "--win-first")
+                .map(|b| Goal::WinFirst(b as i32))
+                .or_else(|| option(&args, "--win-last").map(|b| Goal::WinLast(b as i32)));
+            if let Some(goal) = goal {
+                let mut budget = match Budget::from_args(&args) {
+                    Ok(budget) => budget,
+                    Err(e) => {
+                        println!("{}", e);
+                        process::exit(1);
+                    }
+                };
+                match search(&bingo, goal, &mut budget) {
+                    Ok(Outcome::Found(order)) => {
+                        let order: Vec<String> = order.iter().map(|n| n.to_string()).collect();
+                        println!("{}", order.join(","));
+                    }
+                    Ok(Outcome::Impossible(proof)) => println!("impossible: {:?}", proof),
+                    Err(reason) => {
+                        println!("search stopped ({:?}): {:?}", reason, budget.progress())
+                    }
+                }
+                return;
+            }
+            let res = play(&bingo);
+            report_tie("first", res.first_winners());
+            report_tie("last", res.last_winners());
+            for win in res.first().iter().chain(res.last().iter()) {
+                println!("{}", win.score);
+            }
         }
-    } else {
-        println!("fail to parse {}", args[1]);
+        Err(e) => println!("fail to parse {}: {}", args[1], e),
     }
 }
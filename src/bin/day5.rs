@@ -1,7 +1,30 @@
-use aoc2021::vents::{draw_lines, intersections, parse};
+use aoc2021::generators;
+use aoc2021::y2021;
+use aoc2021::y2021::vent_crossings::intersect;
+use aoc2021::y2021::vent_index::VentIndex;
+use aoc2021::y2021::vents::{overlaps, parse, parse_vent, raster_overlaps, Raster, Vent};
 use std::env;
 use std::process;
 
+fn parse_input(input: &str) -> Vec<Vent> {
+    input.split('\n').filter_map(parse_vent).collect()
+}
+
+/// Compare `overlaps` against counting the vents on each of their positions
+/// on random inputs
+fn fuzz(args: &[String]) {
+    let (seeds, size) = generators::fuzz_args(args, 20);
+    let fast = |input: &str| overlaps(&parse_input(input));
+    let naive = |input: &str| raster_overlaps(&parse_input(input), Raster::Lattice);
+    generators::report(generators::fuzz(
+        seeds,
+        size,
+        y2021::generators::vents,
+        fast,
+        naive,
+    ));
+}
+
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
@@ -9,10 +32,37 @@ fn main() {
         process::exit(1);
     }
 
-    if let Some((vents, max_x, max_y)) = parse(&args[1]) {
-        let mut board = vec![vec![0; max_x]; max_y];
-        draw_lines(&mut board, vents);
-        println!("{}", intersections(&board));
+    if args[1] == "fuzz" {
+        fuzz(&args);
+        return;
+    }
+
+    if let Some((vents, _, _)) = parse(&args[1]) {
+        let densest = args
+            .iter()
+            .position(|a| a == "--densest")
+            .and_then(|i| args.get(i + 1))
+            .and_then(|k| k.parse::<usize>().ok());
+        if let Some(k) = densest {
+            for (pos, n) in VentIndex::new(vents).densest(k) {
+                println!("{},{}: {}", pos.x, pos.y, n);
+            }
+        } else if args.iter().any(|a| a == "--crossings") {
+            let res = intersect(&vents);
+            for c in res.crossings {
+                println!("{}: {:?}", c.point, c.vents);
+            }
+            for o in res.overlaps {
+                println!(
+                    "{},{} -> {},{}: {:?}",
+                    o.from.x, o.from.y, o.to.x, o.to.y, o.vents
+                );
+            }
+        } else if args.iter().any(|a| a == "--bresenham") {
+            println!("{}", raster_overlaps(&vents, Raster::Bresenham));
+        } else {
+            println!("{}", overlaps(&vents));
+        }
     } else {
         println!("fail to parse {}", args[1]);
     }
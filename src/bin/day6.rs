@@ -1,29 +1,39 @@
+use aoc2021::generators;
 use aoc2021::parser::parse_csv;
 use aoc2021::parser::parse_file;
+use aoc2021::y2021::lanternfish::{Population, Simulation, Species};
+use aoc2021::y2021::{self, naive};
 use std::env;
+use std::fs::File;
 use std::process;
 
-fn evolve(fishes: &mut [u64; 9], days: u32) {
-    for _ in 0..days {
-        let mut new_fishes = [0_u64; 9];
-        println!("{:?}", fishes);
-        for i in 1..fishes.len() {
-            new_fishes[i - 1] = fishes[i];
-        }
-        new_fishes[8] = fishes[0];
-        new_fishes[6] += fishes[0];
-        for i in 0..fishes.len() {
-            fishes[i] = new_fishes[i];
-        }
-    }
-}
+const FUZZ_DAYS: u32 = 50;
 
-fn fill(fishes: &mut [u64; 9], lanternfishes: Vec<i64>) {
-    for i in 0..fishes.len() {
-        fishes[i] = 0;
-    }
+/// Compare the population model against a simulation of individual fishes
+/// on random inputs
+fn fuzz(args: &[String]) {
+    let (seeds, size) = generators::fuzz_args(args, 20);
+    let fast = |input: &str| {
+        let timers = parse_csv(&vec![input]).unwrap();
+        let population = Population::from_timers(Species::lanternfish(), &timers).unwrap();
+        let mut sim = Simulation::new(vec![population]);
+        sim.run(FUZZ_DAYS, |_| {}).unwrap();
+        sim.total()
+    };
+    let naive = |input: &str| naive::lanternfish(input, FUZZ_DAYS);
+    generators::report(generators::fuzz(
+        seeds,
+        size,
+        y2021::generators::lanternfish,
+        fast,
+        naive,
+    ));
+}
 
-    lanternfishes.iter().for_each(|f| fishes[*f as usize] += 1);
+/// The value following an option, if it is present
+fn option<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let i = args.iter().position(|a| a == name)?;
+    args.get(i + 1).map(String::as_str)
 }
 
 fn main() {
@@ -33,13 +43,75 @@ fn main() {
         process::exit(1);
     }
 
-    let mut fishes = [0_u64; 9];
+    if args[1] == "fuzz" {
+        fuzz(&args);
+        return;
+    }
 
-    if let Some(lanternfishes) = parse_file(&args[1], parse_csv) {
-        fill(&mut fishes, lanternfishes);
-        evolve(&mut fishes, 256);
-        println!("{}", fishes.iter().fold(0, |n, i| n + i));
+    let days = match option(&args, "--days").map(str::parse) {
+        None => 256,
+        Some(Ok(days)) => days,
+        Some(Err(_)) => {
+            println!("--days expects a number");
+            process::exit(1);
+        }
+    };
+    // every `--species name:cycle:delay:offspring` starts from the input
+    let mut species = vec![];
+    for (i, a) in args.iter().enumerate() {
+        if a == "--species" {
+            match args.get(i + 1).and_then(|s| Species::parse(s)) {
+                Some(s) => species.push(s),
+                None => {
+                    println!("--species expects name:cycle:delay:offspring");
+                    process::exit(1);
+                }
+            }
+        }
+    }
+    if species.is_empty() {
+        species.push(Species::lanternfish());
+    }
+
+    let timers = match parse_file(&args[1], parse_csv) {
+        Some(timers) => timers,
+        None => {
+            println!("fail to parse {}", args[1]);
+            process::exit(1);
+        }
+    };
+    let populations: Option<Vec<Population>> = species
+        .into_iter()
+        .map(|s| Population::from_timers(s, &timers))
+        .collect();
+    let mut sim = match populations {
+        Some(populations) => Simulation::new(populations),
+        None => {
+            println!("a timer is too large for a species");
+            process::exit(1);
+        }
+    };
+
+    // optionally export the counts of every day instead of printing them
+    if let Some(path) = option(&args, "--csv") {
+        let written = File::create(path).and_then(|file| sim.write_csv(days, file));
+        if let Err(e) = written {
+            println!("cannot write time series: {}", e);
+            process::exit(1);
+        }
     } else {
-        println!("fail to parse {}", args[1]);
+        let trace = args.iter().any(|a| a == "--trace");
+        let res = sim.run(days, |s| {
+            if trace {
+                for p in &s.populations {
+                    println!("{} {} {:?}", s.day, p.species.name, p.counts);
+                }
+            }
+        });
+        if let Err(e) = res {
+            println!("{}", e);
+            process::exit(1);
+        }
     }
+    println!("{}", sim.total());
 }
@@ -1,24 +1,9 @@
-use aoc2021::nums::bounds;
-use aoc2021::nums::sum_of_n;
 use aoc2021::parser::parse_csv;
 use aoc2021::parser::parse_file;
-use core::i64::MAX;
-use std::cmp::min;
+use aoc2021::y2021::crabs::{self, CostFn, Linear, Quadratic, Triangular};
 use std::env;
 use std::process;
 
-fn solve(puzzle: &Vec<i64>) -> i64 {
-    let (from, to) = bounds(puzzle);
-    let mut min_fuel = MAX;
-    for i in from..to {
-        min_fuel = min(
-            min_fuel,
-            puzzle.iter().fold(0, |n, j| n + sum_of_n((i - j).abs())),
-        );
-    }
-    min_fuel
-}
-
 fn main() {
     let args: Vec<String> = env::args().collect();
     if args.len() < 2 {
@@ -26,9 +11,50 @@ fn main() {
         process::exit(1);
     }
 
+    let name = args
+        .iter()
+        .position(|a| a == "--cost")
+        .and_then(|i| args.get(i + 1))
+        .map_or("triangular", String::as_str);
+    let cost: &dyn CostFn = match name {
+        "linear" => &Linear,
+        "triangular" => &Triangular,
+        "quadratic" => &Quadratic,
+        _ => {
+            println!("--cost expects linear, triangular or quadratic");
+            process::exit(1);
+        }
+    };
+
+    let points = match args.iter().position(|a| a == "--points") {
+        None => 1,
+        Some(i) => match args.get(i + 1).and_then(|k| k.parse().ok()) {
+            Some(k) => k,
+            None => {
+                println!("--points expects a number");
+                process::exit(1);
+            }
+        },
+    };
+
     if let Some(puzzle) = parse_file(&args[1], parse_csv) {
-        let solution = solve(&puzzle);
-        println!("{}", solution);
+        if points == 1 {
+            match crabs::solve(&puzzle, cost) {
+                Some(best) => println!("{} (position {})", best.fuel, best.position),
+                None => println!("no crab"),
+            }
+        } else {
+            match crabs::solve_k(&puzzle, points, cost) {
+                Some(gathering) => {
+                    println!("{}", gathering.fuel);
+                    for (i, p) in gathering.points.iter().enumerate() {
+                        let crabs = gathering.assignment.iter().filter(|g| **g == i).count();
+                        println!("{} crabs at {} for {}", crabs, p.position, p.fuel);
+                    }
+                }
+                None => println!("no crab"),
+            }
+        }
     } else {
         println!("fail to parse {}", args[1]);
     }
@@ -42,6 +68,6 @@ mod tests {
     fn test_solve_sample() {
         let sample = vec![16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
 
-        assert_eq!(solve(&sample), 168);
+        assert_eq!(crabs::solve(&sample, &Triangular).unwrap().fuel, 168);
     }
 }
@@ -1,5 +1,4 @@
-use aoc2021::nums::neighbours;
-use aoc2021::nums::transform;
+use aoc2021::y2021::basins::{self, Connectivity};
 use std::env;
 use std::fs::read_to_string;
 use std::process;
@@ -14,60 +13,12 @@ fn parse_digits(lines: &Vec<&str>) -> Option<Vec<Vec<u8>>> {
     Some(output)
 }
 
-fn is_low_point(x: &u8, a: &u8, b: &u8, c: &u8, d: &u8) -> bool {
-    x < a && x < b && x < c && x < d
-}
-
-fn find_basins(
-    nums: &Vec<Vec<(u8, u8, u8, u8, u8)>>,
-    low_points: &Vec<(usize, usize)>,
-) -> Vec<u64> {
-    let mut res = vec![];
-    for (i, j) in low_points {
-        let mut basin = vec![];
-        let mut visited = vec![];
-        let mut cur_neighbours = vec![(*i, *j)];
-        while !cur_neighbours.is_empty() {
-            if let Some(cur) = cur_neighbours.pop() {
-                let (x1, y1) = cur;
-                let cur_neigh = neighbours(nums, cur);
-                for (x, y) in cur_neigh {
-                    if nums[y][x].0 != 9
-                        && !visited.contains(&(x, y))
-                        && !basin.contains(&(x, y))
-                        && !cur_neighbours.contains(&(x, y))
-                    {
-                        cur_neighbours.push((x, y));
-                    }
-                }
-                if nums[y1][x1].0 != 9 {
-                    basin.push(cur);
-                }
-                visited.push(cur);
-            }
-        }
-        res.push(basin.len() as u64);
-    }
-
-    res
-}
-
-fn find_low_points(nums: &Vec<Vec<(u8, u8, u8, u8, u8)>>) -> Vec<(usize, usize)> {
-    let mut res = vec![];
-    for (j, row) in nums.iter().enumerate() {
-        for (i, (x, a, b, c, d)) in row.iter().enumerate() {
-            if is_low_point(x, a, b, c, d) {
-                res.push((i, j));
-            }
-        }
-    }
-    res
-}
-
-fn solve(nums: &Vec<Vec<(u8, u8, u8, u8, u8)>>) -> u64 {
-    let mut res = find_basins(&nums, &find_low_points(&nums));
-    res.sort_by(|a, b| b.partial_cmp(a).unwrap());
-    res[0..3].iter().fold(1, |n, b| n * b)
+/// Product of the sizes of the three largest basins
+fn solve(heights: &[Vec<u8>], barrier: u8, connectivity: Connectivity) -> u64 {
+    let res = basins::label(heights, |h| h >= barrier, connectivity);
+    let mut sizes: Vec<u64> = res.basins.iter().map(|b| b.size as u64).collect();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    sizes.iter().take(3).product()
 }
 
 fn main() {
@@ -77,9 +28,26 @@ fn main() {
         process::exit(1);
     }
 
+    // cells from that height up stop the water
+    let barrier = match args.iter().position(|a| a == "--barrier") {
+        None => 9,
+        Some(i) => match args.get(i + 1).and_then(|h| h.parse().ok()) {
+            Some(h) => h,
+            None => {
+                println!("--barrier expects a height");
+                process::exit(1);
+            }
+        },
+    };
+    let connectivity = if args.iter().any(|a| a == "--diagonal") {
+        Connectivity::Eight
+    } else {
+        Connectivity::Four
+    };
+
     if let Ok(input) = read_to_string(&args[1]) {
         if let Some(puzzle) = parse_digits(&input.split("\n").filter(|s| !s.is_empty()).collect()) {
-            let solution = solve(&transform(puzzle));
+            let solution = solve(&puzzle, barrier, connectivity);
             println!("{}", solution);
         }
     } else {
@@ -101,11 +69,29 @@ mod tests {
             vec![9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
         ];
 
-        let res = solve(&transform(sample));
+        let res: u64 = basins::low_points(&sample, Connectivity::Four)
+            .iter()
+            .map(|(x, y)| basins::risk(sample[*y][*x]))
+            .sum();
 
         assert_eq!(res, 15);
     }
 
+    #[test]
+    fn can_solve_part_2() {
+        let sample = vec![
+            vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
+            vec![3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
+            vec![9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
+            vec![8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
+            vec![9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
+        ];
+
+        let res = solve(&sample, 9, Connectivity::Four);
+
+        assert_eq!(res, 1134);
+    }
+
     #[test]
     fn can_find_low_points() {
         let sample = vec![
@@ -116,23 +102,24 @@ mod tests {
             vec![9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
         ];
 
-        let res = find_low_points(&transform(sample));
+        let res = basins::low_points(&sample, Connectivity::Four);
 
         assert_eq!(res, vec![(1, 0), (9, 0), (2, 2), (6, 4)]);
     }
 
     #[test]
     fn can_find_basins() {
-        let sample = transform(vec![
+        let sample = vec![
             vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
             vec![3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
             vec![9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
             vec![8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
             vec![9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
-        ]);
+        ];
 
-        let res = find_basins(&sample, &find_low_points(&sample));
+        let res = basins::label(&sample, |h| h == 9, Connectivity::Four);
 
-        assert_eq!(res, vec![3, 9, 14, 9]);
+        let sizes: Vec<u64> = res.basins.iter().map(|b| b.size as u64).collect();
+        assert_eq!(sizes, vec![3, 9, 14, 9]);
     }
 }
@@ -0,0 +1,283 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+/// The reason why a search was stopped before completion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exhausted {
+    Time,
+    Iterations,
+    Cancelled,
+}
+
+/// A snapshot of the state of some long running search
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Number of nodes expanded (or iterations run) so far
+    pub expanded: u64,
+    /// Size of the queue of pending work at the last tick
+    pub queue: usize,
+    /// Best value found so far, if any, as reported by the search
+    pub best: Option<i64>,
+    pub elapsed: Duration,
+}
+
+type Reporter = Box<dyn FnMut(&Progress) + Send>;
+
+/// A handle passed into long running solvers to bound the time and the
+/// number of iterations they run, and report their progress.
+///
+/// Solvers are expected to call `tick` once per node expanded and stop
+/// as soon as it returns an error.
+pub struct Budget {
+    started: Instant,
+    time_limit: Option<Duration>,
+    max_iterations: Option<u64>,
+    cancelled: Arc<AtomicBool>,
+    expanded: u64,
+    queue: usize,
+    best: Option<i64>,
+    report_rate: Option<Duration>,
+    last_report: Instant,
+    reporter: Reporter,
+}
+
+fn print_progress(progress: &Progress) {
+    println!(
+        "expanded {}, queue {}, best {:?}, elapsed {:?}",
+        progress.expanded, progress.queue, progress.best, progress.elapsed
+    );
+}
+
+impl Budget {
+    /// A budget without any limit which never reports progress
+    pub fn unlimited() -> Budget {
+        let now = Instant::now();
+        Budget {
+            started: now,
+            time_limit: None,
+            max_iterations: None,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            expanded: 0,
+            queue: 0,
+            best: None,
+            report_rate: None,
+            last_report: now,
+            reporter: Box::new(print_progress),
+        }
+    }
+
+    /// Stop the search once `limit` wall-clock time has elapsed since the
+    /// budget was created
+    pub fn with_time_limit(mut self, limit: Duration) -> Budget {
+        self.time_limit = Some(limit);
+        self
+    }
+
+    /// Stop the search after `max` calls to `tick`
+    pub fn with_max_iterations(mut self, max: u64) -> Budget {
+        self.max_iterations = Some(max);
+        self
+    }
+
+    /// Share an existing cancellation flag with this budget
+    pub fn with_cancel_flag(mut self, flag: Arc<AtomicBool>) -> Budget {
+        self.cancelled = flag;
+        self
+    }
+
+    /// Print progress at most once every `rate`
+    pub fn with_progress(mut self, rate: Duration) -> Budget {
+        self.report_rate = Some(rate);
+        self
+    }
+
+    /// Report progress at most once every `rate` through the given function
+    pub fn with_reporter<F: FnMut(&Progress) + Send + 'static>(
+        mut self,
+        rate: Duration,
+        reporter: F,
+    ) -> Budget {
+        self.report_rate = Some(rate);
+        self.reporter = Box::new(reporter);
+        self
+    }
+
+    /// Build a budget from command-line arguments, ignoring unknown ones.
+    /// Understands `--time-limit <secs>`, `--max-iterations <n>` and
+    /// `--progress <secs>`, and fails on an invalid value for them.
+    pub fn from_args(args: &[String]) -> Result<Budget, String> {
+        let mut budget = Budget::unlimited();
+        let mut it = args.iter();
+        while let Some(arg) = it.next() {
+            let value = it.clone().next().map(String::as_str);
+            let secs = || {
+                value
+                    .and_then(|v| v.parse::<f64>().ok())
+                    .and_then(|secs| Duration::try_from_secs_f64(secs).ok())
+                    .ok_or_else(|| format!("{} expects a number of seconds", arg))
+            };
+            match arg.as_str() {
+                "--time-limit" => budget = budget.with_time_limit(secs()?),
+                "--progress" => budget = budget.with_progress(secs()?),
+                "--max-iterations" => {
+                    let max = value
+                        .and_then(|v| v.parse::<u64>().ok())
+                        .ok_or_else(|| format!("{} expects a number of iterations", arg))?;
+                    budget = budget.with_max_iterations(max)
+                }
+                _ => continue,
+            }
+            it.next();
+        }
+        Ok(budget)
+    }
+
+    /// A flag which, when set from anywhere, cancels the search at its next tick
+    pub fn cancel_flag(&self) -> Arc<AtomicBool> {
+        self.cancelled.clone()
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Record the best value found so far by the search
+    pub fn best(&mut self, value: i64) {
+        self.best = Some(value);
+    }
+
+    /// Account for one more node expanded, with `queue` nodes still pending.
+    /// Returns an error if the search should stop now.
+    pub fn tick(&mut self, queue: usize) -> Result<(), Exhausted> {
+        self.expanded += 1;
+        self.queue = queue;
+
+        if let Some(rate) = self.report_rate {
+            if self.last_report.elapsed() >= rate {
+                self.last_report = Instant::now();
+                let progress = self.progress();
+                (self.reporter)(&progress);
+            }
+        }
+
+        self.check()
+    }
+
+    /// Check whether the budget is exhausted without consuming an iteration
+    pub fn check(&self) -> Result<(), Exhausted> {
+        if self.cancelled.load(Ordering::Relaxed) {
+            return Err(Exhausted::Cancelled);
+        }
+        if let Some(max) = self.max_iterations {
+            if self.expanded > max {
+                return Err(Exhausted::Iterations);
+            }
+        }
+        if let Some(limit) = self.time_limit {
+            if self.started.elapsed() >= limit {
+                return Err(Exhausted::Time);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            expanded: self.expanded,
+            queue: self.queue,
+            best: self.best,
+            elapsed: self.started.elapsed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn unlimited_budget_never_stops() {
+        let mut budget = Budget::unlimited();
+        for i in 0..1000 {
+            assert_eq!(budget.tick(i), Ok(()));
+        }
+        assert_eq!(budget.progress().expanded, 1000);
+    }
+
+    #[test]
+    fn stops_after_max_iterations() {
+        let mut budget = Budget::unlimited().with_max_iterations(3);
+
+        assert_eq!(budget.tick(1), Ok(()));
+        assert_eq!(budget.tick(1), Ok(()));
+        assert_eq!(budget.tick(1), Ok(()));
+        assert_eq!(budget.check(), Ok(()));
+        assert_eq!(budget.tick(1), Err(Exhausted::Iterations));
+        assert_eq!(budget.check(), Err(Exhausted::Iterations));
+    }
+
+    #[test]
+    fn stops_on_time_limit() {
+        let mut budget = Budget::unlimited().with_time_limit(Duration::from_secs(0));
+
+        assert_eq!(budget.tick(1), Err(Exhausted::Time));
+    }
+
+    #[test]
+    fn stops_when_cancelled_from_another_thread() {
+        let mut budget = Budget::unlimited();
+        let flag = budget.cancel_flag();
+
+        std::thread::spawn(move || flag.store(true, Ordering::Relaxed))
+            .join()
+            .unwrap();
+
+        assert_eq!(budget.tick(1), Err(Exhausted::Cancelled));
+    }
+
+    #[test]
+    fn reports_progress_with_best_value() {
+        let reports = Arc::new(Mutex::new(vec![]));
+        let sink = reports.clone();
+        let mut budget = Budget::unlimited().with_reporter(Duration::from_secs(0), move |p| {
+            sink.lock().unwrap().push(*p)
+        });
+
+        budget.tick(10).unwrap();
+        budget.best(42);
+        budget.tick(12).unwrap();
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.len(), 2);
+        assert_eq!(reports[1].expanded, 2);
+        assert_eq!(reports[1].queue, 12);
+        assert_eq!(reports[1].best, Some(42));
+    }
+
+    #[test]
+    fn parse_budget_from_args() {
+        let args = |args: &[&str]| -> Vec<String> { args.iter().map(|s| s.to_string()).collect() };
+        let mut budget = Budget::from_args(&args(&[
+            "input.txt",
+            "--max-iterations",
+            "2",
+            "--progress",
+            "1",
+        ]))
+        .unwrap();
+
+        assert_eq!(budget.tick(1), Ok(()));
+        assert_eq!(budget.tick(1), Ok(()));
+        assert_eq!(budget.tick(1), Err(Exhausted::Iterations));
+
+        for invalid in ["2.9", "-1", "many"] {
+            assert!(Budget::from_args(&args(&["--max-iterations", invalid])).is_err());
+        }
+        assert!(Budget::from_args(&args(&["--time-limit", "-1"])).is_err());
+        assert!(Budget::from_args(&args(&["--progress"])).is_err());
+    }
+}
@@ -1,13 +1,162 @@
-use std::cmp::Ordering::Less;
+use num::zero;
+use num::Num;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
 
-/// Count the number items in a vector that are greater
+/// Count the number items in a sequence that are greater
 /// than the previous one.
-pub fn count_increasing(windows: &Vec<i32>) -> usize {
-    let windows_tail = &windows[1..];
-    windows
-        .iter()
-        .zip(windows_tail.iter())
-        .map(|(a, b)| a.cmp(b))
-        .filter(|ord| *ord == Less)
-        .count()
+pub fn count_increasing<I>(items: I) -> usize
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    trend(items).increasing
+}
+
+/// An iterator over the sums of every `width` consecutive values of another
+/// iterator, keeping only the last `width` values in memory
+pub struct WindowSums<I: Iterator> {
+    iter: I,
+    width: usize,
+    window: VecDeque<I::Item>,
+    sum: I::Item,
+}
+
+impl<I> Iterator for WindowSums<I>
+where
+    I: Iterator,
+    I::Item: Num + Copy,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        while self.window.len() < self.width {
+            let value = self.iter.next()?;
+            self.window.push_back(value);
+            self.sum = self.sum + value;
+        }
+        let res = self.sum;
+        let oldest = self.window.pop_front().unwrap();
+        self.sum = self.sum - oldest;
+        Some(res)
+    }
+}
+
+/// Sums of the sliding windows of `width` values over some sequence
+pub fn window_sums<I>(items: I, width: usize) -> WindowSums<I::IntoIter>
+where
+    I: IntoIterator,
+    I::Item: Num + Copy,
+{
+    assert!(width > 0, "windows must hold at least one value");
+    WindowSums {
+        iter: items.into_iter(),
+        width,
+        window: VecDeque::with_capacity(width),
+        sum: zero(),
+    }
+}
+
+/// How a sequence evolves between consecutive values: number of
+/// transitions of each kind, and length (in values) of the longest
+/// strictly monotone runs
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Trend {
+    pub increasing: usize,
+    pub decreasing: usize,
+    pub flat: usize,
+    pub longest_increasing: usize,
+    pub longest_decreasing: usize,
+}
+
+/// Compute the `Trend` of some sequence in a single pass
+pub fn trend<I>(items: I) -> Trend
+where
+    I: IntoIterator,
+    I::Item: PartialOrd,
+{
+    let mut res = Trend::default();
+    let mut items = items.into_iter();
+    let mut previous = match items.next() {
+        Some(first) => first,
+        None => return res,
+    };
+    res.longest_increasing = 1;
+    res.longest_decreasing = 1;
+    let (mut up_run, mut down_run) = (1, 1);
+    for item in items {
+        match previous.partial_cmp(&item) {
+            Some(Ordering::Less) => {
+                res.increasing += 1;
+                up_run += 1;
+                down_run = 1;
+            }
+            Some(Ordering::Greater) => {
+                res.decreasing += 1;
+                up_run = 1;
+                down_run += 1;
+            }
+            _ => {
+                res.flat += 1;
+                up_run = 1;
+                down_run = 1;
+            }
+        }
+        res.longest_increasing = res.longest_increasing.max(up_run);
+        res.longest_decreasing = res.longest_decreasing.max(down_run);
+        previous = item;
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEPTHS: [i32; 10] = [199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
+
+    #[test]
+    fn count_increasing_depths_and_windows() {
+        assert_eq!(count_increasing(DEPTHS.iter()), 7);
+        assert_eq!(count_increasing(window_sums(DEPTHS.iter().copied(), 3)), 5);
+        assert_eq!(count_increasing(Vec::<i32>::new()), 0);
+    }
+
+    #[test]
+    fn window_sums_of_any_width() {
+        let sums: Vec<i32> = window_sums(DEPTHS.iter().copied(), 3).collect();
+        assert_eq!(sums, vec![607, 618, 618, 617, 647, 716, 769, 792]);
+
+        assert_eq!(
+            window_sums(1..=4_u64, 1).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(window_sums(1..=4_u64, 4).collect::<Vec<_>>(), vec![10]);
+        assert_eq!(window_sums(1..=4_u64, 5).count(), 0);
+    }
+
+    #[test]
+    fn trend_counts_transitions_and_runs() {
+        let res = trend(window_sums(DEPTHS.iter().copied(), 3));
+
+        assert_eq!(
+            res,
+            Trend {
+                increasing: 5,
+                decreasing: 1,
+                flat: 1,
+                longest_increasing: 5,
+                longest_decreasing: 2,
+            }
+        );
+        assert_eq!(trend(Vec::<f64>::new()), Trend::default());
+    }
+
+    #[test]
+    fn trend_streams_long_sequences() {
+        let res = trend((0..1_000_000_u64).map(|i| i % 1000));
+
+        assert_eq!(res.longest_increasing, 1000);
+        assert_eq!(res.decreasing, 999);
+    }
 }
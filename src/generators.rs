@@ -0,0 +1,161 @@
+//! Seeded random inputs, used to compare solvers against their naive
+//! counterparts. The generators for each puzzle live with their year, eg.
+//! `y2021::generators`
+use std::fmt::Debug;
+use std::process;
+
+/// A small deterministic pseudo-random numbers generator (splitmix64)
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `[0, n)`
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    /// A number in `[lo, hi)`
+    pub fn range(&mut self, lo: i64, hi: i64) -> i64 {
+        lo + self.below((hi - lo) as u64) as i64
+    }
+
+    /// Fisher-Yates shuffle of some slice
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+/// A mismatch between 2 solvers found by `fuzz`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch<T> {
+    pub seed: u64,
+    pub input: String,
+    pub fast: T,
+    pub naive: T,
+}
+
+/// Generate inputs of the given size for seeds `0..seeds` and compare the
+/// answers of `fast` and `naive` solvers on each of them.
+/// Returns the number of inputs checked or the first mismatch found.
+pub fn fuzz<T, G, F, N>(
+    seeds: u64,
+    size: usize,
+    generate: G,
+    fast: F,
+    naive: N,
+) -> Result<u64, Mismatch<T>>
+where
+    T: PartialEq + Debug,
+    G: Fn(&mut Rng, usize) -> String,
+    F: Fn(&str) -> T,
+    N: Fn(&str) -> T,
+{
+    for seed in 0..seeds {
+        let input = generate(&mut Rng::new(seed), size);
+        let (f, n) = (fast(&input), naive(&input));
+        if f != n {
+            return Err(Mismatch {
+                seed,
+                input,
+                fast: f,
+                naive: n,
+            });
+        }
+    }
+    Ok(seeds)
+}
+
+/// Print the outcome of `fuzz`, exiting with an error on mismatch
+pub fn report<T: Debug>(res: Result<u64, Mismatch<T>>) {
+    match res {
+        Ok(n) => println!("{} inputs checked, no mismatch", n),
+        Err(m) => {
+            println!(
+                "mismatch for seed {}: fast {:?}, naive {:?}\n{}",
+                m.seed, m.fast, m.naive, m.input
+            );
+            process::exit(1);
+        }
+    }
+}
+
+/// Read `--seeds N` and `--size S` from command-line arguments
+pub fn fuzz_args(args: &[String], default_size: usize) -> (u64, usize) {
+    let value = |name: &str| {
+        args.iter()
+            .position(|a| a == name)
+            .and_then(|i| args.get(i + 1))
+            .and_then(|v| v.parse::<u64>().ok())
+    };
+    (
+        value("--seeds").unwrap_or(100),
+        value("--size").map_or(default_size, |s| s as usize),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rng_is_deterministic() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let xs: Vec<u64> = (0..10).map(|_| a.next_u64()).collect();
+        let ys: Vec<u64> = (0..10).map(|_| b.next_u64()).collect();
+
+        assert_eq!(xs, ys);
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+
+    #[test]
+    fn rng_range_stays_within_bounds() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let v = rng.range(-3, 4);
+            assert!((-3..4).contains(&v));
+        }
+    }
+
+    #[test]
+    fn shuffle_keeps_all_items() {
+        let mut items: Vec<u32> = (0..50).collect();
+        Rng::new(3).shuffle(&mut items);
+        let mut sorted = items.clone();
+        sorted.sort();
+
+        assert_ne!(items, sorted);
+        assert_eq!(sorted, (0..50).collect::<Vec<u32>>());
+    }
+
+    #[test]
+    fn fuzz_reports_first_mismatch() {
+        let res = fuzz(
+            10,
+            3,
+            |rng, size| (0..size).map(|_| rng.range(0, 9).to_string()).collect(),
+            |s| s.len(),
+            |s| if s.contains('8') { 0 } else { s.len() },
+        );
+
+        assert!(res.is_err());
+        assert!(res.unwrap_err().input.contains('8'));
+    }
+}
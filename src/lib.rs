@@ -1,8 +1,11 @@
-pub mod bingo;
+pub mod budget;
 pub mod derivative;
 pub mod files;
+pub mod generators;
 pub mod geometry;
 pub mod kruskal;
 pub mod nums;
+pub mod parallel;
 pub mod parser;
-pub mod vents;
+pub mod runner;
+pub mod y2021;
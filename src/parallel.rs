@@ -0,0 +1,75 @@
+use std::thread;
+
+/// Number of worker threads requested on the command-line with `--jobs N`,
+/// defaulting to the available parallelism of the machine
+pub fn jobs_from_args(args: &[String]) -> usize {
+    let requested = args
+        .iter()
+        .position(|a| a == "--jobs")
+        .and_then(|i| args.get(i + 1))
+        .and_then(|n| n.parse::<usize>().ok());
+    match requested {
+        Some(n) => n.max(1),
+        None => thread::available_parallelism().map_or(1, |n| n.get()),
+    }
+}
+
+/// Apply `f` to every item using up to `jobs` threads.
+///
+/// Items are split in contiguous chunks, one per thread, and results are
+/// concatenated in chunk order so the output is the same as a sequential
+/// `items.iter().map(f).collect()` whatever the number of jobs.
+pub fn par_map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    if jobs <= 1 || items.len() <= 1 {
+        return items.iter().map(f).collect();
+    }
+    let chunk_size = items.len().div_ceil(jobs);
+    let f = &f;
+    thread::scope(|s| {
+        let workers: Vec<_> = items
+            .chunks(chunk_size)
+            .map(|chunk| s.spawn(move || chunk.iter().map(f).collect::<Vec<R>>()))
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().unwrap())
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn par_map_preserves_order() {
+        let items: Vec<u64> = (0..1000).collect();
+        let sequential: Vec<u64> = items.iter().map(|i| i * i).collect();
+
+        for jobs in 1..9 {
+            assert_eq!(par_map(&items, jobs, |i| i * i), sequential);
+        }
+    }
+
+    #[test]
+    fn par_map_handles_more_jobs_than_items() {
+        assert_eq!(par_map(&[1, 2], 16, |i| i + 1), vec![2, 3]);
+        assert_eq!(par_map(&[] as &[u8], 4, |i| *i), vec![]);
+    }
+
+    #[test]
+    fn read_jobs_from_args() {
+        let args: Vec<String> = ["input.txt", "--jobs", "3"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        assert_eq!(jobs_from_args(&args), 3);
+        assert!(jobs_from_args(&args[..1]) >= 1);
+    }
+}
@@ -5,9 +5,12 @@ use nom::character::complete::digit1;
 use nom::character::complete::space1;
 use nom::combinator::map;
 use nom::combinator::map_res;
+use nom::combinator::opt;
+use nom::combinator::recognize;
 use nom::error::Error;
 use nom::multi::many1;
 use nom::multi::separated_list1;
+use nom::sequence::pair;
 use nom::sequence::tuple;
 use nom::Err;
 use nom::IResult;
@@ -32,18 +35,30 @@ pub fn num(input: &str) -> IResult<&str, i32> {
     map_res(digit1, |s: &str| s.parse::<i32>())(input)
 }
 
-/// Parse a single move order
-/// This function does not try to interpret the moves, it  only
-/// parses them and produce typed structure representing the move.
-pub fn parse_move(input: &str) -> Option<(Move, i32)> {
+/// Parse a single integer, possibly negative
+pub fn signed(input: &str) -> IResult<&str, i64> {
+    map_res(recognize(pair(opt(char('-')), digit1)), |s: &str| {
+        s.parse::<i64>()
+    })(input)
+}
+
+/// Parse a single move order, as a nom parser
+pub fn move_order(input: &str) -> IResult<&str, (Move, i32)> {
     let up = map(tag("up"), |_| Move::Up);
     let down = map(tag("down"), |_| Move::Down);
     let fwd = map(tag("forward"), |_| Move::Forward);
     let mov = alt((up, down, fwd));
 
-    let res: Result<_, E> = tuple((mov, space1, num))(input);
+    map(tuple((mov, space1, num)), |(m, _, n)| (m, n))(input)
+}
+
+/// Parse a single move order
+/// This function does not try to interpret the moves, it  only
+/// parses them and produce typed structure representing the move.
+pub fn parse_move(input: &str) -> Option<(Move, i32)> {
+    let res: Result<_, E> = move_order(input);
     match res {
-        Ok((_, (m, _, n))) => Some((m, n)),
+        Ok((_, m)) => Some(m),
         Err(_) => None,
     }
 }
@@ -0,0 +1,217 @@
+use crate::y2021;
+use nom::Err;
+use nom::IResult;
+use nom::Offset;
+use std::fmt;
+use std::fmt::Display;
+use std::fs::read_dir;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::path::PathBuf;
+
+/// A failure to parse some puzzle input, located at some line and column
+/// (both starting at 1)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "parse error at line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+/// A registered solution for the puzzle of some year and day: parse the whole
+/// input and solve it, returning the answers as strings, one per part
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub year: u16,
+    pub day: u8,
+    pub solve: fn(&str) -> Result<Vec<String>, ParseError>,
+}
+
+/// All the solutions which can run from the library, for every year
+pub fn solutions() -> Vec<Solution> {
+    y2021::solutions()
+}
+
+/// The latest year with registered solutions
+pub fn latest_year() -> u16 {
+    solutions()
+        .iter()
+        .map(|s| s.year)
+        .max()
+        .unwrap_or(y2021::YEAR)
+}
+
+pub fn find(year: u16, day: u8) -> Option<Solution> {
+    solutions()
+        .into_iter()
+        .find(|s| s.year == year && s.day == day)
+}
+
+/// The directory holding the inputs of some puzzle, ie. `inputs/<year>/<day>/`
+pub fn input_dir(year: u16, day: u8) -> PathBuf {
+    ["inputs", &year.to_string(), &day.to_string()]
+        .iter()
+        .collect()
+}
+
+/// Extension of the files holding the expected answers for an input file,
+/// one per line, eg. `sample.expected` for `sample`
+pub const EXPECTED: &str = "expected";
+
+/// The input and sample files for some puzzle, ie. every file in its
+/// `input_dir` but expected answers
+pub fn input_files(year: u16, day: u8) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = read_dir(input_dir(year, day))
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok().map(|e| e.path()))
+                .filter(|p| p.is_file() && p.extension().is_none_or(|e| e != EXPECTED))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// The expected answers for some input file, if they are known
+pub fn expected_answers(file: &Path) -> Option<Vec<String>> {
+    let mut name = file.file_name()?.to_os_string();
+    name.push(".");
+    name.push(EXPECTED);
+    let answers = read_to_string(file.with_file_name(name)).ok()?;
+    Some(answers.lines().map(|l| l.trim().to_string()).collect())
+}
+
+/// The non-empty lines of `input`, with their number starting at 1
+pub fn numbered_lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| (i + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+/// Parse every non-empty line of `input` with `parser`, which fails with the
+/// byte offset in the line where parsing stopped, reporting the first line
+/// which fails to parse
+pub fn parse_lines<T>(
+    input: &str,
+    parser: impl Fn(&str) -> Result<T, usize>,
+) -> Result<Vec<T>, ParseError> {
+    let mut res = vec![];
+    for (number, line) in numbered_lines(input) {
+        match parser(line) {
+            Ok(v) => res.push(v),
+            Err(offset) => {
+                return Err(ParseError {
+                    line: number,
+                    column: line[..offset].chars().count() + 1,
+                    message: format!("cannot parse '{}'", line),
+                })
+            }
+        }
+    }
+    Ok(res)
+}
+
+/// Run a nom parser on a whole line but its surrounding blanks, failing with
+/// the offset where it stopped
+pub fn parse_whole<'a, T>(
+    line: &'a str,
+    mut parser: impl FnMut(&'a str) -> IResult<&'a str, T>,
+) -> Result<T, usize> {
+    match parser(line.trim()) {
+        Ok(("", v)) => Ok(v),
+        Ok((rest, _)) => Err(line.offset(rest)),
+        Err(Err::Error(e)) | Err(Err::Failure(e)) => Err(line.offset(e.input)),
+        Err(Err::Incomplete(_)) => Err(line.trim_end().len()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::move_order;
+
+    #[test]
+    fn report_line_and_column_of_parse_errors() {
+        let position = |input: &str| {
+            parse_lines(input, |line| parse_whole(line, move_order)).map_err(|e| (e.line, e.column))
+        };
+
+        assert_eq!(
+            position("forward 5\ndown 5\n\n  sideways 3\nup 2"),
+            Err((4, 3))
+        );
+        assert_eq!(position("forward 5\ndown five"), Err((2, 6)));
+        assert_eq!(position("up 5 \ndown 5 and more"), Err((2, 7)));
+        assert_eq!(position(" up 5 \n\ndown 5\n").map(|m| m.len()), Ok(2));
+    }
+
+    #[test]
+    fn report_positions_of_puzzle_errors() {
+        let position = |day: u8, input: &str| {
+            (find(2021, day).unwrap().solve)(input).map_err(|e| (e.line, e.column))
+        };
+
+        assert_eq!(position(1, "199\n\n 2x0\n"), Err((3, 2)));
+        assert_eq!(position(3, "0101\n0111\n01a1"), Err((3, 3)));
+        assert_eq!(position(4, "1,2\n\n1 2\n3 4\n\n4 5\n 6 5"), Err((7, 4)));
+        assert_eq!(position(4, "1,2\n"), Err((2, 1)));
+        assert_eq!(position(5, "0,9 -> 5,9\n8,0 => 0,8"), Err((2, 5)));
+        assert_eq!(position(6, "3,4,3\n1, 12"), Err((2, 4)));
+        // twenty eights do not fit in 64 bits
+        let digits = "abcefg cf acdeg acdfg bcdf abdfg abdefg acf abcdefg abcdfg";
+        let eights = vec!["abcdefg"; 20].join(" ");
+        let day8 = format!("{} | cf\n{} | {}", digits, digits, eights);
+        assert_eq!(position(8, &day8), Err((2, digits.len() + 4)));
+        assert_eq!(position(9, "2199\n39x7"), Err((2, 3)));
+    }
+
+    #[test]
+    fn verify_registered_days_on_samples() {
+        for solution in solutions() {
+            for file in input_files(solution.year, solution.day) {
+                if file.file_name().is_some_and(|n| n != "input.txt") {
+                    let input = read_to_string(&file).unwrap();
+                    assert_eq!((solution.solve)(&input).ok(), expected_answers(&file));
+                }
+            }
+        }
+        let files = input_files(2021, 9);
+        assert_eq!(
+            files,
+            [
+                input_dir(2021, 9).join("input.txt"),
+                input_dir(2021, 9).join("sample")
+            ]
+        );
+    }
+
+    #[test]
+    fn solve_registered_days_on_samples() {
+        let day1 = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+        let day2 = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+
+        assert_eq!(
+            (find(2021, 1).unwrap().solve)(day1),
+            Ok(vec!["7".to_string(), "5".to_string()])
+        );
+        assert_eq!(
+            (find(2021, 2).unwrap().solve)(day2),
+            Ok(vec!["150".to_string(), "900".to_string()])
+        );
+        assert!(find(2021, 25).is_none());
+        assert!(find(2020, 1).is_none());
+    }
+}
@@ -0,0 +1,253 @@
+//! Basins of a heightmap: the regions of cells connected without crossing a
+//! barrier, labelled with a union-find over the cells
+use disjoint_sets::UnionFind;
+use std::collections::HashMap;
+
+/// Which cells touch each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Up, down, left and right
+    Four,
+    /// Diagonals too
+    Eight,
+}
+
+impl Connectivity {
+    /// Offsets to the neighbours after a cell in reading order, so that
+    /// each pair of neighbours is seen once
+    fn forward(self) -> &'static [(isize, usize)] {
+        match self {
+            Connectivity::Four => &[(1, 0), (0, 1)],
+            Connectivity::Eight => &[(1, 0), (-1, 1), (0, 1), (1, 1)],
+        }
+    }
+
+    fn all(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &[(0, -1), (-1, 0), (1, 0), (0, 1)],
+            Connectivity::Eight => &[
+                (-1, -1),
+                (0, -1),
+                (1, -1),
+                (-1, 0),
+                (1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+/// Risk level of a cell
+pub fn risk(height: u8) -> u64 {
+    height as u64 + 1
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Basin {
+    /// Number of cells
+    pub size: usize,
+    /// Sum of the risk levels of the cells
+    pub risk: u64,
+    /// The lowest cell as `(x, y)`, the first one in reading order on ties
+    pub low_point: (usize, usize),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Basins {
+    /// Index in `basins` of the basin of each cell, `None` on barriers
+    pub labels: Vec<Vec<Option<usize>>>,
+    /// Basins in the reading order of their first cell
+    pub basins: Vec<Basin>,
+}
+
+/// Label the basins of a heightmap, rows possibly having different widths
+pub fn label<F: Fn(u8) -> bool>(
+    heights: &[Vec<u8>],
+    is_barrier: F,
+    connectivity: Connectivity,
+) -> Basins {
+    let mut offsets = vec![0];
+    for row in heights {
+        offsets.push(offsets.last().unwrap() + row.len());
+    }
+    let index = |x: usize, y: usize| offsets[y] + x;
+    let open = |x: usize, y: usize| heights[y].get(x).is_some_and(|h| !is_barrier(*h));
+
+    let mut cells = UnionFind::new(*offsets.last().unwrap());
+    for (y, row) in heights.iter().enumerate() {
+        for x in 0..row.len() {
+            if !open(x, y) {
+                continue;
+            }
+            for (dx, dy) in connectivity.forward() {
+                let (nx, ny) = match x.checked_add_signed(*dx) {
+                    Some(nx) => (nx, y + dy),
+                    None => continue,
+                };
+                if ny < heights.len() && open(nx, ny) {
+                    cells.union(index(x, y), index(nx, ny));
+                }
+            }
+        }
+    }
+
+    let mut labels = vec![];
+    let mut basins: Vec<Basin> = vec![];
+    let mut label_of_root = HashMap::new();
+    for (y, row) in heights.iter().enumerate() {
+        let mut row_labels = vec![];
+        for (x, h) in row.iter().enumerate() {
+            if !open(x, y) {
+                row_labels.push(None);
+                continue;
+            }
+            let next = basins.len();
+            let label = *label_of_root.entry(cells.find(index(x, y))).or_insert(next);
+            if label == next {
+                basins.push(Basin {
+                    size: 0,
+                    risk: 0,
+                    low_point: (x, y),
+                });
+            }
+            let basin = &mut basins[label];
+            basin.size += 1;
+            basin.risk += risk(*h);
+            let (lx, ly) = basin.low_point;
+            if *h < heights[ly][lx] {
+                basin.low_point = (x, y);
+            }
+            row_labels.push(Some(label));
+        }
+        labels.push(row_labels);
+    }
+    Basins { labels, basins }
+}
+
+/// The cells lower than all their neighbours, as `(x, y)` in reading order
+pub fn low_points(heights: &[Vec<u8>], connectivity: Connectivity) -> Vec<(usize, usize)> {
+    let mut res = vec![];
+    for (y, row) in heights.iter().enumerate() {
+        for (x, h) in row.iter().enumerate() {
+            let lower = connectivity.all().iter().all(|(dx, dy)| {
+                let neighbour = y
+                    .checked_add_signed(*dy)
+                    .and_then(|ny| heights.get(ny))
+                    .zip(x.checked_add_signed(*dx))
+                    .and_then(|(r, nx)| r.get(nx));
+                neighbour.is_none_or(|n| h < n)
+            });
+            if lower {
+                res.push((x, y));
+            }
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Vec<u8>> {
+        vec![
+            vec![2, 1, 9, 9, 9, 4, 3, 2, 1, 0],
+            vec![3, 9, 8, 7, 8, 9, 4, 9, 2, 1],
+            vec![9, 8, 5, 6, 7, 8, 9, 8, 9, 2],
+            vec![8, 7, 6, 7, 8, 9, 6, 7, 8, 9],
+            vec![9, 8, 9, 9, 9, 6, 5, 6, 7, 8],
+        ]
+    }
+
+    #[test]
+    fn label_sample_basins() {
+        let res = label(&sample(), |h| h == 9, Connectivity::Four);
+
+        let sizes: Vec<usize> = res.basins.iter().map(|b| b.size).collect();
+        assert_eq!(sizes, [3, 9, 14, 9]);
+        let low: Vec<(usize, usize)> = res.basins.iter().map(|b| b.low_point).collect();
+        assert_eq!(low, [(1, 0), (9, 0), (2, 2), (6, 4)]);
+        assert_eq!(res.basins[0].risk, 3 + 2 + 4);
+        assert_eq!(res.labels[0][..3], [Some(0), Some(0), None]);
+        assert_eq!(res.labels[4][9], Some(3));
+    }
+
+    #[test]
+    fn find_sample_low_points() {
+        let low = low_points(&sample(), Connectivity::Four);
+        assert_eq!(low, [(1, 0), (9, 0), (2, 2), (6, 4)]);
+        let risk: u64 = low.iter().map(|(x, y)| risk(sample()[*y][*x])).sum();
+        assert_eq!(risk, 15);
+        assert_eq!(low_points(&sample(), Connectivity::Eight).len(), 4);
+    }
+
+    #[test]
+    fn configurable_barriers_and_connectivity() {
+        // basins only meet through a corner
+        let heights = vec![vec![1, 9], vec![9, 2]];
+        assert_eq!(
+            label(&heights, |h| h == 9, Connectivity::Four).basins.len(),
+            2
+        );
+        let res = label(&heights, |h| h == 9, Connectivity::Eight);
+        assert_eq!(res.basins.len(), 1);
+        assert_eq!(res.basins[0].low_point, (0, 0));
+        assert_eq!(res.labels, [[Some(0), None], [None, Some(0)]]);
+
+        // anything from 7 up shrinks the largest sample basin
+        let res = label(&sample(), |h| h >= 7, Connectivity::Four);
+        let sizes: Vec<usize> = res.basins.iter().map(|b| b.size).collect();
+        let open = sample().iter().flatten().filter(|h| **h < 7).count();
+        assert_eq!(sizes.iter().sum::<usize>(), open);
+        assert_eq!(sizes, [3, 9, 3, 4]);
+
+        // nothing stops the water, and rows of any width
+        let ragged = vec![vec![3, 4, 5], vec![6], vec![2, 9]];
+        let res = label(&ragged, |_| false, Connectivity::Four);
+        assert_eq!(res.basins.len(), 1);
+        assert_eq!(res.basins[0].low_point, (0, 2));
+        assert_eq!(res.basins[0].risk, 35);
+    }
+
+    #[test]
+    fn same_sizes_as_a_flood_fill() {
+        let mut rng = crate::generators::Rng::new(9);
+        for connectivity in [Connectivity::Four, Connectivity::Eight] {
+            for _ in 0..30 {
+                let heights: Vec<Vec<u8>> = (0..12)
+                    .map(|_| (0..15).map(|_| rng.below(10) as u8).collect())
+                    .collect();
+                let res = label(&heights, |h| h == 9, connectivity);
+
+                // flood each basin from its first cell
+                for (label, basin) in res.basins.iter().enumerate() {
+                    let mut seen = vec![vec![false; 15]; 12];
+                    let start = (0..12)
+                        .flat_map(|y| (0..15).map(move |x| (x, y)))
+                        .find(|(x, y)| res.labels[*y][*x] == Some(label))
+                        .unwrap();
+                    let mut stack = vec![start];
+                    seen[start.1][start.0] = true;
+                    let mut size = 0;
+                    while let Some((x, y)) = stack.pop() {
+                        size += 1;
+                        assert_eq!(res.labels[y][x], Some(label));
+                        for (dx, dy) in connectivity.all() {
+                            let (nx, ny) = (x as isize + dx, y as isize + dy);
+                            if (0..15).contains(&nx) && (0..12).contains(&ny) {
+                                let (nx, ny) = (nx as usize, ny as usize);
+                                if heights[ny][nx] != 9 && !seen[ny][nx] {
+                                    seen[ny][nx] = true;
+                                    stack.push((nx, ny));
+                                }
+                            }
+                        }
+                    }
+                    assert_eq!(size, basin.size);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1229 @@
+use nom::bytes::complete::tag;
+use nom::character::complete::digit1;
+use nom::character::complete::space1;
+use nom::combinator::all_consuming;
+use nom::combinator::map_res;
+use nom::error::Error;
+use nom::multi::separated_list1;
+use nom::Err;
+use nom::IResult;
+use nom::Offset;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::convert::TryInto;
+use std::fmt;
+use std::fmt::Display;
+use std::fs::read_to_string;
+
+type E<'a> = Err<Error<&'a str>>;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Cell {
+    number: i32,
+    drawn: bool,
+}
+
+impl Cell {
+    pub fn number(&self) -> i32 {
+        self.number
+    }
+
+    pub fn drawn(&self) -> bool {
+        self.drawn
+    }
+}
+
+/// A board of any number of rows and columns, cells stored row by row
+#[derive(Debug, PartialEq, Clone)]
+pub struct Board {
+    num: i32,
+    rows: usize,
+    cols: usize,
+    cells: Vec<Cell>,
+}
+
+/// A position on a board, as (row, column)
+pub type Pos = (usize, usize);
+
+/// A way to win on a board
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Pattern {
+    /// Any full row
+    Rows,
+    /// Any full column
+    Columns,
+    /// Any of the 2 diagonals, on square boards only
+    Diagonals,
+    /// The 4 corners
+    Corners,
+    /// Every cell of the board
+    Blackout,
+    /// Every cell set in the mask, which must have the size of the board
+    Mask(Vec<Vec<bool>>),
+}
+
+impl Pattern {
+    /// The rules of the puzzle: any full row or column
+    pub fn standard() -> Vec<Pattern> {
+        vec![Pattern::Rows, Pattern::Columns]
+    }
+
+    /// Parse a pattern name, or a mask given as `mask:` followed by rows of
+    /// 0 and 1 separated by `/`, eg. `mask:101/010/101`
+    pub fn parse(input: &str) -> Option<Pattern> {
+        match input {
+            "rows" => Some(Pattern::Rows),
+            "columns" => Some(Pattern::Columns),
+            "diagonals" => Some(Pattern::Diagonals),
+            "corners" => Some(Pattern::Corners),
+            "blackout" => Some(Pattern::Blackout),
+            _ => {
+                let rows = input.strip_prefix("mask:")?;
+                rows.split('/')
+                    .map(|row| {
+                        row.chars()
+                            .map(|c| match c {
+                                '0' => Some(false),
+                                '1' => Some(true),
+                                _ => None,
+                            })
+                            .collect()
+                    })
+                    .collect::<Option<Vec<Vec<bool>>>>()
+                    .map(Pattern::Mask)
+            }
+        }
+    }
+
+    /// The lines of positions on a `rows`x`cols` board, any of which
+    /// completes the pattern once all its cells are drawn
+    pub fn lines(&self, rows: usize, cols: usize) -> Vec<Vec<Pos>> {
+        match self {
+            Pattern::Rows => (0..rows)
+                .map(|j| (0..cols).map(|i| (j, i)).collect())
+                .collect(),
+            Pattern::Columns => (0..cols)
+                .map(|i| (0..rows).map(|j| (j, i)).collect())
+                .collect(),
+            Pattern::Diagonals if rows == cols => vec![
+                (0..rows).map(|k| (k, k)).collect(),
+                (0..rows).map(|k| (k, cols - 1 - k)).collect(),
+            ],
+            Pattern::Diagonals => vec![],
+            Pattern::Corners => vec![vec![
+                (0, 0),
+                (0, cols - 1),
+                (rows - 1, 0),
+                (rows - 1, cols - 1),
+            ]],
+            Pattern::Blackout => vec![(0..rows)
+                .flat_map(|j| (0..cols).map(move |i| (j, i)))
+                .collect()],
+            Pattern::Mask(mask) => {
+                if mask.len() != rows || mask.iter().any(|row| row.len() != cols) {
+                    return vec![];
+                }
+                vec![(0..rows)
+                    .flat_map(|j| (0..cols).map(move |i| (j, i)))
+                    .filter(|(j, i)| mask[*j][*i])
+                    .collect()]
+            }
+        }
+    }
+}
+
+impl Board {
+    /// A board with the given rows of numbers, none drawn yet.
+    /// Returns None if there is no number or rows have different lengths.
+    pub fn new(num: i32, numbers: Vec<Vec<i32>>) -> Option<Board> {
+        let cols = numbers.first()?.len();
+        if cols == 0 || numbers.iter().any(|row| row.len() != cols) {
+            return None;
+        }
+        Some(Board {
+            num,
+            rows: numbers.len(),
+            cols,
+            cells: numbers
+                .into_iter()
+                .flatten()
+                .map(|number| Cell {
+                    number,
+                    drawn: false,
+                })
+                .collect(),
+        })
+    }
+
+    pub fn num(&self) -> i32 {
+        self.num
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn cell(&self, (row, col): Pos) -> Cell {
+        self.cells[row * self.cols + col]
+    }
+
+    /// Mark every cell with the drawn number, returning the positions of
+    /// the cells which were not drawn yet
+    pub fn mark(&mut self, drawn: i32) -> Vec<Pos> {
+        let cols = self.cols;
+        self.cells
+            .iter_mut()
+            .enumerate()
+            .filter(|(_, cell)| cell.number == drawn && !cell.drawn)
+            .map(|(k, cell)| {
+                cell.drawn = true;
+                (k / cols, k % cols)
+            })
+            .collect()
+    }
+
+    /// The first line of some pattern with all its cells drawn, if any
+    pub fn winning_line(&self, patterns: &[Pattern]) -> Option<Vec<Pos>> {
+        patterns
+            .iter()
+            .flat_map(|p| p.lines(self.rows, self.cols))
+            .find(|line| !line.is_empty() && line.iter().all(|pos| self.cell(*pos).drawn))
+    }
+}
+
+impl Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for j in 0..self.rows {
+            for i in 0..self.cols {
+                let c = self.cell((j, i));
+                if c.drawn {
+                    write!(f, "{:^5}", c.number)?;
+                } else {
+                    write!(f, "[{:^3}]", c.number)?;
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct Bingo {
+    /// The current list of 'random' numbers
+    draw: Vec<i32>,
+    /// The boards
+    boards: Vec<Board>,
+    /// The ways a board can win
+    patterns: Vec<Pattern>,
+    /// How to order boards winning on the same draw
+    tie_break: TieBreak,
+    index: Index,
+}
+
+/// How to order boards winning on the same draw, which decides the first
+/// and last winners of a game
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TieBreak {
+    /// In input order
+    BoardOrder,
+    /// Higher scores first
+    HighestScore,
+    /// Lower scores first
+    LowestScore,
+}
+
+impl TieBreak {
+    pub fn parse(input: &str) -> Option<TieBreak> {
+        match input {
+            "board" => Some(TieBreak::BoardOrder),
+            "highest" => Some(TieBreak::HighestScore),
+            "lowest" => Some(TieBreak::LowestScore),
+            _ => None,
+        }
+    }
+
+    /// Sort wins of the same draw according to the policy
+    fn sort(&self, wins: &mut [Win]) {
+        match self {
+            TieBreak::BoardOrder => wins.sort_by_key(|w| w.board),
+            TieBreak::HighestScore => wins.sort_by_key(|w| (-w.score, w.board)),
+            TieBreak::LowestScore => wins.sort_by_key(|w| (w.score, w.board)),
+        }
+    }
+}
+
+/// A malformed bingo input, lines starting at 1
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum BingoError {
+    /// The first line is not a list of comma-separated numbers, parsing
+    /// stopping at some column
+    InvalidDraw {
+        column: usize,
+    },
+    /// Some board row is not a list of space-separated numbers
+    InvalidRow {
+        line: usize,
+        column: usize,
+    },
+    /// Some board row does not have as many numbers as the first one, the
+    /// column being after the numbers of the shortest row
+    RaggedBoard {
+        line: usize,
+        column: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// The column is the one of the second occurrence of the number
+    DuplicateNumber {
+        board: i32,
+        number: i32,
+        line: usize,
+        column: usize,
+    },
+    NoBoard,
+}
+
+impl Display for BingoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BingoError::InvalidDraw { .. } => write!(f, "invalid drawn numbers"),
+            BingoError::InvalidRow { line, .. } => write!(f, "invalid board row at line {}", line),
+            BingoError::RaggedBoard {
+                line,
+                expected,
+                found,
+                ..
+            } => write!(
+                f,
+                "board row at line {} has {} numbers, expected {}",
+                line, found, expected
+            ),
+            BingoError::DuplicateNumber { board, number, .. } => {
+                write!(f, "board {} has {} more than once", board, number)
+            }
+            BingoError::NoBoard => write!(f, "no board"),
+        }
+    }
+}
+
+/// The lines of the win patterns on boards of some size, as cell indices,
+/// and the lines each cell belongs to
+#[derive(Debug, PartialEq, Clone)]
+struct Shape {
+    rows: usize,
+    cols: usize,
+    lines: Vec<Vec<usize>>,
+    cell_lines: Vec<Vec<usize>>,
+}
+
+/// Where each number appears on the boards, so that drawing a number only
+/// touches its occurrences
+#[derive(Debug, PartialEq, Clone)]
+struct Index {
+    /// Occurrences of each number, as (board index, cell index)
+    occurrences: HashMap<i32, Vec<(usize, usize)>>,
+    shapes: Vec<Shape>,
+    /// Index in `shapes` for each board
+    board_shape: Vec<usize>,
+}
+
+impl Index {
+    fn new(boards: &[Board], patterns: &[Pattern]) -> Index {
+        let mut index = Index {
+            occurrences: HashMap::new(),
+            shapes: vec![],
+            board_shape: vec![],
+        };
+        for (b, board) in boards.iter().enumerate() {
+            let (rows, cols) = (board.rows, board.cols);
+            let shape = match index
+                .shapes
+                .iter()
+                .position(|s| s.rows == rows && s.cols == cols)
+            {
+                Some(shape) => shape,
+                None => {
+                    let lines: Vec<Vec<usize>> = patterns
+                        .iter()
+                        .flat_map(|p| p.lines(rows, cols))
+                        .filter(|line| !line.is_empty())
+                        .map(|line| line.iter().map(|(j, i)| j * cols + i).collect())
+                        .collect();
+                    let mut cell_lines = vec![vec![]; rows * cols];
+                    for (l, line) in lines.iter().enumerate() {
+                        for cell in line {
+                            cell_lines[*cell].push(l);
+                        }
+                    }
+                    index.shapes.push(Shape {
+                        rows,
+                        cols,
+                        lines,
+                        cell_lines,
+                    });
+                    index.shapes.len() - 1
+                }
+            };
+            index.board_shape.push(shape);
+            for (k, cell) in board.cells.iter().enumerate() {
+                index
+                    .occurrences
+                    .entry(cell.number)
+                    .or_default()
+                    .push((b, k));
+            }
+        }
+        index
+    }
+
+    fn shape(&self, board: usize) -> &Shape {
+        &self.shapes[self.board_shape[board]]
+    }
+}
+
+impl Bingo {
+    /// A game with the standard win patterns
+    pub fn new(draw: Vec<i32>, boards: Vec<Board>) -> Bingo {
+        let patterns = Pattern::standard();
+        Bingo {
+            index: Index::new(&boards, &patterns),
+            draw,
+            boards,
+            patterns,
+            tie_break: TieBreak::BoardOrder,
+        }
+    }
+
+    /// Order boards winning on the same draw with some other policy than
+    /// the input order
+    pub fn with_tie_break(mut self, tie_break: TieBreak) -> Bingo {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Play with other win patterns than the standard rows and columns
+    pub fn with_patterns(mut self, patterns: Vec<Pattern>) -> Bingo {
+        self.index = Index::new(&self.boards, &patterns);
+        self.patterns = patterns;
+        self
+    }
+
+    pub fn draw(&self) -> &[i32] {
+        &self.draw
+    }
+
+    pub fn boards(&self) -> &[Board] {
+        &self.boards
+    }
+
+    /// The numbers of each line of the win patterns on the board at `index`
+    /// (its position in `boards`)
+    pub fn line_numbers(&self, index: usize) -> Vec<Vec<i32>> {
+        let board = &self.boards[index];
+        self.index
+            .shape(index)
+            .lines
+            .iter()
+            .map(|line| line.iter().map(|k| board.cells[*k].number).collect())
+            .collect()
+    }
+
+    /// The boards which cannot win whatever the draw order, as no line of
+    /// the win patterns is made of drawn numbers only
+    pub fn unwinnable(&self) -> Vec<i32> {
+        let drawn: HashSet<i32> = self.draw.iter().copied().collect();
+        self.boards
+            .iter()
+            .enumerate()
+            .filter(|(b, board)| {
+                !self
+                    .index
+                    .shape(*b)
+                    .lines
+                    .iter()
+                    .any(|line| line.iter().all(|k| drawn.contains(&board.cells[*k].number)))
+            })
+            .map(|(_, board)| board.num)
+            .collect()
+    }
+}
+
+/// Column, starting at 1, of a byte offset in a line
+fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+/// Parse a whole line of numbers separated by `sep`, with their column, or
+/// fail with the column where parsing stopped
+fn parse_numbers<'a, F>(sep: F, line: &'a str) -> Result<Vec<(usize, i32)>, usize>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, &'a str>,
+{
+    let num = map_res(digit1, |s: &'a str| {
+        s.parse::<i32>().map(|n| (column(line, line.offset(s)), n))
+    });
+    let res: Result<_, E> = all_consuming(separated_list1(sep, num))(line.trim());
+    match res {
+        Ok((_, ns)) => Ok(ns),
+        Err(Err::Error(e)) | Err(Err::Failure(e)) => Err(column(line, line.offset(e.input))),
+        Err(Err::Incomplete(_)) => Err(column(line, line.trim_end().len())),
+    }
+}
+
+/// Parse a board from its rows, given with their line number
+fn parse_chunk(idx: usize, chunk: &[(usize, &str)]) -> Result<Board, BingoError> {
+    let mut rows: Vec<Vec<(usize, i32)>> = vec![];
+    for (line, input) in chunk {
+        let row = parse_numbers(space1, input).map_err(|column| BingoError::InvalidRow {
+            line: *line,
+            column,
+        })?;
+        if let Some(first) = rows.first() {
+            if first.len() != row.len() {
+                let column = match row.get(first.len()) {
+                    Some((column, _)) => *column,
+                    None => column(input, input.trim_end().len()),
+                };
+                return Err(BingoError::RaggedBoard {
+                    line: *line,
+                    column,
+                    expected: first.len(),
+                    found: row.len(),
+                });
+            }
+        }
+        rows.push(row);
+    }
+    let num = idx.try_into().unwrap();
+    let mut seen = HashSet::new();
+    for (row, (line, _)) in rows.iter().zip(chunk) {
+        for (column, number) in row {
+            if !seen.insert(*number) {
+                return Err(BingoError::DuplicateNumber {
+                    board: num,
+                    number: *number,
+                    line: *line,
+                    column: *column,
+                });
+            }
+        }
+    }
+    let rows = rows
+        .into_iter()
+        .map(|row| row.into_iter().map(|(_, n)| n).collect())
+        .collect();
+    Ok(Board::new(num, rows).unwrap())
+}
+
+/// Parse boards separated by empty lines, starting at line `first_line`
+fn parse_boards(input: &[&str], first_line: usize) -> Result<Vec<Board>, BingoError> {
+    let lines: Vec<(usize, &str)> = input
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (i + first_line, *s))
+        .collect();
+    lines
+        .split(|(_, s)| s.trim().is_empty())
+        .filter(|chunk| !chunk.is_empty())
+        .enumerate()
+        .map(|(i, chunk)| parse_chunk(i, chunk))
+        .collect()
+}
+
+/// Parse and validate some bingo input
+pub fn try_parse_bingo(input: &[&str]) -> Result<Bingo, BingoError> {
+    let first = input.first().ok_or(BingoError::InvalidDraw { column: 1 })?;
+    let draw = parse_numbers(tag(","), first)
+        .map_err(|column| BingoError::InvalidDraw { column })?
+        .into_iter()
+        .map(|(_, n)| n)
+        .collect();
+    let boards = parse_boards(&input[1..], 2)?;
+    if boards.is_empty() {
+        return Err(BingoError::NoBoard);
+    }
+    Ok(Bingo::new(draw, boards))
+}
+
+pub fn parse_bingo(input: &Vec<&str>) -> Option<Bingo> {
+    try_parse_bingo(input).ok()
+}
+
+pub fn parse(file: &str) -> Option<Bingo> {
+    if let Ok(input) = read_to_string(file) {
+        return parse_bingo(&input.split("\n").collect::<Vec<&str>>());
+    }
+    None
+}
+
+pub fn sum_undrawn(board: &Board) -> i32 {
+    board
+        .cells
+        .iter()
+        .filter(|cell| !cell.drawn)
+        .map(|cell| cell.number)
+        .sum()
+}
+
+/// A board completing one of the win patterns
+#[derive(Debug, PartialEq, Clone)]
+pub struct Win {
+    /// Number of the board, in input order
+    pub board: i32,
+    /// Index of the winning draw, starting at 0
+    pub turn: usize,
+    pub number: i32,
+    pub line: Vec<Pos>,
+    pub score: i32,
+}
+
+/// What happens during a game, in order
+#[derive(Debug, PartialEq, Clone)]
+pub enum Event {
+    Drawn { turn: usize, number: i32 },
+    Marked { turn: usize, board: i32, pos: Pos },
+    Won(Win),
+}
+
+/// The state of a game being played: marked cells, number of cells left
+/// to draw on each line of each board and boards which already won
+struct Game<'a> {
+    bingo: &'a Bingo,
+    boards: Vec<Board>,
+    missing: Vec<Vec<usize>>,
+    undrawn: Vec<i32>,
+    won: Vec<bool>,
+    playing: usize,
+}
+
+impl<'a> Game<'a> {
+    fn new(bingo: &'a Bingo) -> Game<'a> {
+        let boards = bingo.boards.clone();
+        Game {
+            missing: (0..boards.len())
+                .map(|b| bingo.index.shape(b).lines.iter().map(|l| l.len()).collect())
+                .collect(),
+            undrawn: boards.iter().map(sum_undrawn).collect(),
+            won: vec![false; boards.len()],
+            playing: boards.len(),
+            boards,
+            bingo,
+        }
+    }
+
+    /// Mark a drawn number on the boards still playing, calling `on_mark`
+    /// with each newly marked cell, and return the boards winning with it
+    /// (in board order) with their first completed line
+    fn draw(&mut self, number: i32, mut on_mark: impl FnMut(usize, Pos)) -> Vec<(usize, usize)> {
+        let mut wins: Vec<(usize, usize)> = vec![];
+        let occurrences = match self.bingo.index.occurrences.get(&number) {
+            Some(occurrences) => occurrences,
+            None => return wins,
+        };
+        for &(b, k) in occurrences {
+            let board = &mut self.boards[b];
+            if self.won[b] || board.cells[k].drawn {
+                continue;
+            }
+            board.cells[k].drawn = true;
+            self.undrawn[b] -= number;
+            on_mark(b, (k / board.cols, k % board.cols));
+            for &l in &self.bingo.index.shape(b).cell_lines[k] {
+                self.missing[b][l] -= 1;
+                if self.missing[b][l] == 0 {
+                    wins.push((b, l));
+                }
+            }
+        }
+        wins.sort_unstable();
+        wins.dedup_by_key(|(b, _)| *b);
+        for (b, _) in &wins {
+            self.won[*b] = true;
+            self.playing -= 1;
+        }
+        wins
+    }
+
+    fn win(&self, b: usize, line: usize, turn: usize, number: i32) -> Win {
+        let board = &self.boards[b];
+        Win {
+            board: board.num,
+            turn,
+            number,
+            line: self.bingo.index.shape(b).lines[line]
+                .iter()
+                .map(|k| (k / board.cols, k % board.cols))
+                .collect(),
+            score: self.undrawn[b] * number,
+        }
+    }
+
+    /// The wins of boards on some draw, ordered by the tie-break policy
+    fn wins(&self, wins: Vec<(usize, usize)>, turn: usize, number: i32) -> Vec<Win> {
+        let mut wins: Vec<Win> = wins
+            .into_iter()
+            .map(|(b, line)| self.win(b, line, turn, number))
+            .collect();
+        self.bingo.tie_break.sort(&mut wins);
+        wins
+    }
+}
+
+/// The events of a game, played lazily. Boards stop being marked once they
+/// won, and the game stops when every board won or all numbers are drawn.
+pub struct Events<'a> {
+    game: Game<'a>,
+    turn: usize,
+    pending: VecDeque<Event>,
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        while self.pending.is_empty() {
+            let bingo = self.game.bingo;
+            if self.game.playing == 0 || self.turn >= bingo.draw.len() {
+                return None;
+            }
+            let turn = self.turn;
+            let number = bingo.draw[turn];
+            self.pending.push_back(Event::Drawn { turn, number });
+            let pending = &mut self.pending;
+            let wins = self.game.draw(number, |b, pos| {
+                pending.push_back(Event::Marked {
+                    turn,
+                    board: bingo.boards[b].num,
+                    pos,
+                })
+            });
+            let wins = self.game.wins(wins, turn, number);
+            self.pending.extend(wins.into_iter().map(Event::Won));
+            self.turn += 1;
+        }
+        self.pending.pop_front()
+    }
+}
+
+/// Replay a game event by event, without changing the bingo
+pub fn events(bingo: &Bingo) -> Events<'_> {
+    Events {
+        game: Game::new(bingo),
+        turn: 0,
+        pending: VecDeque::new(),
+    }
+}
+
+/// The outcome of a game: the boards which won, in winning order, boards
+/// winning on the same draw being ordered by the tie-break policy
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct GameResult {
+    pub wins: Vec<Win>,
+}
+
+impl GameResult {
+    /// Every group of boards winning together on the same draw
+    pub fn ties(&self) -> Vec<&[Win]> {
+        self.wins
+            .chunk_by(|a, b| a.turn == b.turn)
+            .filter(|wins| wins.len() > 1)
+            .collect()
+    }
+
+    /// All the boards winning on the first winning draw
+    pub fn first_winners(&self) -> &[Win] {
+        self.wins
+            .chunk_by(|a, b| a.turn == b.turn)
+            .next()
+            .unwrap_or(&[])
+    }
+
+    /// All the boards winning on the last winning draw
+    pub fn last_winners(&self) -> &[Win] {
+        self.wins
+            .chunk_by(|a, b| a.turn == b.turn)
+            .next_back()
+            .unwrap_or(&[])
+    }
+
+    pub fn first(&self) -> Option<&Win> {
+        self.wins.first()
+    }
+
+    pub fn last(&self) -> Option<&Win> {
+        self.wins.last()
+    }
+
+    /// How some board won, if it did
+    pub fn board(&self, num: i32) -> Option<&Win> {
+        self.wins.iter().find(|w| w.board == num)
+    }
+}
+
+/// Play a whole game, until every board won or all numbers are drawn
+pub fn play(bingo: &Bingo) -> GameResult {
+    play_draws(bingo, &bingo.draw)
+}
+
+/// Play the boards of a bingo with some other draw order
+pub fn play_draws(bingo: &Bingo, draws: &[i32]) -> GameResult {
+    let mut game = Game::new(bingo);
+    let mut res = GameResult::default();
+    for (turn, number) in draws.iter().enumerate() {
+        if game.playing == 0 {
+            break;
+        }
+        let wins = game.draw(*number, |_, _| {});
+        res.wins.extend(game.wins(wins, turn, *number));
+    }
+    res
+}
+
+/// The bingo of the puzzle statement, shared by the tests of the bingo modules
+#[cfg(test)]
+pub(crate) fn sample() -> Bingo {
+    let input = "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7";
+    parse_bingo(&input.split('\n').collect()).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_winning(board: &Board, patterns: &[Pattern]) -> bool {
+        board.winning_line(patterns).is_some()
+    }
+
+    fn board(num: i32, numbers: Vec<Vec<i32>>) -> Board {
+        Board::new(num, numbers).unwrap()
+    }
+
+    #[test]
+    fn test_play_updates_boards() {
+        let mut numbers = vec![vec![10; 5]; 5];
+        numbers[4][3] = 12;
+        let bingo = Bingo::new(vec![12; 1], vec![board(0, numbers)]);
+        let mut game = Game::new(&bingo);
+        let mut marked = vec![];
+
+        let wins = game.draw(12, |b, pos| marked.push((b, pos)));
+
+        assert_eq!(wins, vec![]);
+        assert_eq!(marked, vec![(0, (4, 3))]);
+        assert!(game.boards[0].cell((4, 3)).drawn);
+        assert!(!game.boards[0].cell((3, 4)).drawn);
+        assert!(!bingo.boards[0].cell((4, 3)).drawn);
+    }
+
+    fn has_winning_board(bingo: &Bingo) -> Option<Board> {
+        for board in &bingo.boards {
+            if is_winning(board, &bingo.patterns) {
+                return Some(board.clone());
+            }
+        }
+        None
+    }
+
+    #[test]
+    fn test_has_winning_board_when_full_row_has_true() {
+        let mut numbers = vec![vec![10; 5]; 5];
+        numbers[3] = vec![12; 5];
+        let mut winning = board(0, numbers);
+        winning.mark(12);
+        let not_winning = board(0, vec![vec![10; 5]; 5]);
+
+        let bingo = Bingo::new(vec![], vec![winning.clone(), not_winning]);
+
+        let some_winning = has_winning_board(&bingo);
+
+        assert_eq!(some_winning, Some(winning));
+    }
+
+    #[test]
+    fn test_has_winning_board_when_full_column_has_true() {
+        let mut winning = board(0, vec![vec![12, 10, 10, 10, 10]; 5]);
+        winning.mark(12);
+        let not_winning = board(0, vec![vec![10; 5]; 5]);
+
+        let bingo = Bingo::new(vec![], vec![winning.clone(), not_winning]);
+
+        let some_winning = has_winning_board(&bingo);
+
+        assert_eq!(some_winning, Some(winning));
+    }
+
+    #[test]
+    fn test_parse_boards() {
+        assert_eq!(
+            parse_bingo(&vec![
+                "1,2,3,4",
+                "",
+                "1 2 3 4 5",
+                "6 7 8 9 10",
+                "11 12 13 14 15",
+                "16 17 18 19 20",
+                "21 22 23 24 25",
+            ]),
+            Some(Bingo::new(
+                vec![1, 2, 3, 4],
+                vec![board(
+                    0,
+                    (0..5)
+                        .map(|j| (1..=5).map(|i| j * 5 + i).collect())
+                        .collect()
+                )]
+            ))
+        );
+    }
+
+    #[test]
+    fn test_can_parse_sample_bingo() {
+        let input = vec![
+            "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1",
+            "",
+            "22 13 17 11  0",
+            " 8  2 23  4 24",
+            "21  9 14 16  7",
+            " 6 10  3 18  5",
+            " 1 12 20 15 19",
+            "",
+            " 3 15  0  2 22",
+            " 9 18 13 17  5",
+            "19  8  7 25 23",
+            "20 11 10 24  4",
+            "14 21 16 12  6",
+            "",
+            "14 21 17 24  4",
+            "10 16 15  9 19",
+            "18  8 23 26 20",
+            "22 11 13  6  5",
+            " 2  0 12  3  7",
+        ];
+        assert_eq!(
+            parse_bingo(&input),
+            Some(Bingo::new(
+                vec![
+                    7, 4, 9, 5, 11, 17, 23, 2, 0, 14, 21, 24, 10, 16, 13, 6, 15, 25, 12, 22, 18,
+                    20, 8, 19, 3, 26, 1
+                ],
+                vec![
+                    board(
+                        0,
+                        vec![
+                            vec![22, 13, 17, 11, 0],
+                            vec![8, 2, 23, 4, 24],
+                            vec![21, 9, 14, 16, 7],
+                            vec![6, 10, 3, 18, 5],
+                            vec![1, 12, 20, 15, 19],
+                        ]
+                    ),
+                    board(
+                        1,
+                        vec![
+                            vec![3, 15, 0, 2, 22],
+                            vec![9, 18, 13, 17, 5],
+                            vec![19, 8, 7, 25, 23],
+                            vec![20, 11, 10, 24, 4],
+                            vec![14, 21, 16, 12, 6],
+                        ]
+                    ),
+                    board(
+                        2,
+                        vec![
+                            vec![14, 21, 17, 24, 4],
+                            vec![10, 16, 15, 9, 19],
+                            vec![18, 8, 23, 26, 20],
+                            vec![22, 11, 13, 6, 5],
+                            vec![2, 0, 12, 3, 7],
+                        ]
+                    ),
+                ],
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_boards_of_any_size() {
+        let input = vec!["1,2", "", "1 2 3", "4 5 6", "", "7 8", "9 10", "11 12", ""];
+        let bingo = parse_bingo(&input).unwrap();
+
+        assert_eq!(bingo.boards.len(), 2);
+        assert_eq!((bingo.boards[0].rows(), bingo.boards[0].cols()), (2, 3));
+        assert_eq!((bingo.boards[1].rows(), bingo.boards[1].cols()), (3, 2));
+        assert_eq!(bingo.boards[1].cell((2, 1)).number(), 12);
+        assert_eq!(Board::new(0, vec![vec![1, 2], vec![3]]), None);
+    }
+
+    fn marked(numbers: &[i32]) -> Board {
+        let mut b = board(0, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]);
+        for n in numbers {
+            b.mark(*n);
+        }
+        b
+    }
+
+    #[test]
+    fn win_with_configurable_patterns() {
+        let diagonals = [Pattern::Diagonals];
+        assert_eq!(
+            marked(&[3, 5, 7]).winning_line(&diagonals),
+            Some(vec![(0, 2), (1, 1), (2, 0)])
+        );
+        assert!(!is_winning(&marked(&[3, 5, 7]), &Pattern::standard()));
+        assert!(!is_winning(&marked(&[1, 2, 3]), &diagonals));
+
+        assert!(is_winning(&marked(&[1, 3, 7, 9]), &[Pattern::Corners]));
+        assert!(!is_winning(&marked(&[1, 3, 7]), &[Pattern::Corners]));
+
+        let all: Vec<i32> = (1..=9).collect();
+        assert!(is_winning(&marked(&all), &[Pattern::Blackout]));
+        assert!(!is_winning(&marked(&all[1..]), &[Pattern::Blackout]));
+
+        let cross = Pattern::parse("mask:010/111/010").unwrap();
+        assert!(is_winning(
+            &marked(&[2, 4, 5, 6, 8]),
+            std::slice::from_ref(&cross)
+        ));
+        assert!(!is_winning(&marked(&[2, 4, 5, 6]), &[cross]));
+
+        let too_small = Pattern::parse("mask:11/11").unwrap();
+        assert!(!is_winning(&marked(&all), &[too_small]));
+        assert_eq!(Pattern::parse("mask:12"), None);
+        assert_eq!(Pattern::parse("stripes"), None);
+    }
+
+    #[test]
+    fn diagonals_only_count_on_square_boards() {
+        let mut b = board(0, vec![vec![1, 2, 3], vec![4, 5, 6]]);
+        b.mark(1);
+        b.mark(5);
+
+        assert!(Pattern::Diagonals.lines(2, 3).is_empty());
+        assert!(!is_winning(&b, &[Pattern::Diagonals]));
+    }
+
+    #[test]
+    fn play_sample_game() {
+        let bingo = sample();
+
+        let res = play(&bingo);
+
+        assert_eq!(res.wins.len(), 3);
+        assert_eq!(
+            res.first(),
+            Some(&Win {
+                board: 2,
+                turn: 11,
+                number: 24,
+                line: vec![(0, 0), (0, 1), (0, 2), (0, 3), (0, 4)],
+                score: 4512,
+            })
+        );
+        assert_eq!(res.last().map(|w| (w.board, w.score)), Some((1, 1924)));
+        assert_eq!(res.board(0).map(|w| w.turn), Some(13));
+        assert_eq!(play(&bingo), res);
+    }
+
+    #[test]
+    fn replay_sample_events() {
+        let events: Vec<Event> = events(&sample()).collect();
+
+        assert_eq!(events[0], Event::Drawn { turn: 0, number: 7 });
+        assert_eq!(
+            &events[1..4],
+            &[
+                Event::Marked {
+                    turn: 0,
+                    board: 0,
+                    pos: (2, 4)
+                },
+                Event::Marked {
+                    turn: 0,
+                    board: 1,
+                    pos: (2, 2)
+                },
+                Event::Marked {
+                    turn: 0,
+                    board: 2,
+                    pos: (4, 4)
+                },
+            ]
+        );
+        let wins: Vec<i32> = events
+            .iter()
+            .filter_map(|e| match e {
+                Event::Won(w) => Some(w.board),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(wins, vec![2, 0, 1]);
+        // the game stops once the last board won, on 13
+        assert_eq!(
+            events
+                .iter()
+                .filter(|e| matches!(e, Event::Drawn { .. }))
+                .count(),
+            15
+        );
+    }
+
+    #[test]
+    fn index_marks_only_occurrences() {
+        let bingo = sample();
+
+        assert_eq!(bingo.index.occurrences[&7], vec![(0, 14), (1, 12), (2, 24)]);
+        assert_eq!(bingo.index.occurrences.get(&99), None);
+        assert_eq!(bingo.index.shapes.len(), 1);
+        assert_eq!(bingo.index.shapes[0].lines.len(), 10);
+        assert_eq!(bingo.index.shapes[0].cell_lines[6], vec![1, 6]);
+    }
+
+    #[test]
+    fn win_on_patterns_with_index() {
+        let bingo = Bingo::new(
+            vec![5, 1, 9, 3, 7],
+            vec![board(0, vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]])],
+        )
+        .with_patterns(vec![Pattern::Corners, Pattern::Diagonals]);
+
+        let res = play(&bingo);
+
+        assert_eq!(res.wins.len(), 1);
+        assert_eq!(res.wins[0].turn, 2);
+        assert_eq!(res.wins[0].line, vec![(0, 0), (1, 1), (2, 2)]);
+        assert_eq!(res.wins[0].score, (2 + 3 + 4 + 6 + 7 + 8) * 9);
+    }
+
+    #[test]
+    fn play_many_boards_with_other_draw_orders() {
+        let boards: Vec<Board> = (0..2000)
+            .map(|b| {
+                let numbers = (0..5)
+                    .map(|j| (0..5).map(|i| (b * 7 + j * 5 + i) % 100).collect())
+                    .collect();
+                board(b, numbers)
+            })
+            .collect();
+        let draws: Vec<i32> = (0..100).rev().collect();
+        let bingo = Bingo::new((0..100).collect(), boards);
+
+        let forward = play(&bingo);
+        let backward = play_draws(&bingo, &draws);
+
+        assert_eq!(forward.wins.len(), 2000);
+        assert_eq!(backward.wins.len(), 2000);
+        assert_eq!(forward.first().map(|w| w.turn), Some(4));
+        assert_eq!(backward.first().map(|w| w.turn), Some(4));
+    }
+
+    #[test]
+    fn report_malformed_input() {
+        let parse = |input: &str| try_parse_bingo(&input.split('\n').collect::<Vec<&str>>());
+
+        assert_eq!(
+            parse("1,2,x\n\n1 2\n3 4").err(),
+            Some(BingoError::InvalidDraw { column: 4 })
+        );
+        assert_eq!(
+            parse("1,2\n\n1 2\n3 a").err(),
+            Some(BingoError::InvalidRow { line: 4, column: 2 })
+        );
+        assert_eq!(
+            parse("1,2\n\n1 2\n3 4\n\n1 2\n3 4 5").err(),
+            Some(BingoError::RaggedBoard {
+                line: 7,
+                column: 5,
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            parse("1,2\n\n1  2\n3 4\n\n1 2 3\n4").err(),
+            Some(BingoError::RaggedBoard {
+                line: 7,
+                column: 2,
+                expected: 3,
+                found: 1
+            })
+        );
+        assert_eq!(
+            parse("1,2\n\n1 2\n3 4\n\n 1 2\n 3 1").err(),
+            Some(BingoError::DuplicateNumber {
+                board: 1,
+                number: 1,
+                line: 7,
+                column: 4
+            })
+        );
+        assert_eq!(parse("1,2\n\n").err(), Some(BingoError::NoBoard));
+        assert!(parse("1,2\n\n1 2\n3 4\n").is_ok());
+    }
+
+    #[test]
+    fn detect_unwinnable_boards() {
+        let bingo = Bingo::new(
+            vec![1, 2, 5],
+            vec![
+                board(0, vec![vec![1, 2], vec![3, 4]]),
+                board(1, vec![vec![1, 3], vec![5, 4]]),
+                board(2, vec![vec![1, 3], vec![4, 5]]),
+            ],
+        );
+
+        assert_eq!(bingo.unwinnable(), vec![2]);
+        let bingo = bingo.with_patterns(vec![Pattern::Diagonals]);
+        assert_eq!(bingo.unwinnable(), vec![0, 1]);
+    }
+
+    #[test]
+    fn order_simultaneous_winners_by_policy() {
+        let boards = vec![
+            board(0, vec![vec![1, 2], vec![3, 4]]),
+            board(1, vec![vec![1, 9], vec![2, 8]]),
+            board(2, vec![vec![5, 6], vec![1, 2]]),
+            board(3, vec![vec![3, 5], vec![4, 7]]),
+        ];
+        let bingo = Bingo::new(vec![1, 2, 3, 4], boards);
+
+        let res = play(&bingo);
+        let order: Vec<i32> = res.wins.iter().map(|w| w.board).collect();
+        assert_eq!(order, vec![0, 1, 2, 3]);
+        assert_eq!(res.ties().len(), 1);
+        assert_eq!(res.first_winners().len(), 3);
+        assert_eq!(res.last_winners().len(), 1);
+        assert_eq!(res.first().map(|w| w.board), Some(0));
+
+        let res = play(&bingo.clone().with_tie_break(TieBreak::HighestScore));
+        let scores: Vec<i32> = res.first_winners().iter().map(|w| w.score).collect();
+        assert_eq!(scores, vec![34, 22, 14]);
+        assert_eq!(res.first().map(|w| w.board), Some(1));
+
+        let res = play(&bingo.with_tie_break(TieBreak::LowestScore));
+        assert_eq!(res.first().map(|w| w.board), Some(0));
+        assert_eq!(res.wins[2].board, 1);
+    }
+}
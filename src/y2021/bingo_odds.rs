@@ -0,0 +1,208 @@
+//! Monte Carlo estimation of how good each bingo board is over random draw
+//! orders of the same numbers
+use crate::generators::Rng;
+use crate::parallel::par_map;
+use crate::y2021::bingo::{play_draws, Bingo};
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::ops::Range;
+
+/// Outcome of the simulated games for one board
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoardStats {
+    pub board: i32,
+    pub trials: u64,
+    /// Number of games the board won at some point
+    pub won: u64,
+    /// Number of games the board won first
+    pub first: u64,
+    /// Number of games the board won last, counting only the games which
+    /// every board won
+    pub last: u64,
+    /// Sum of the number of draws needed to win, over the games won
+    pub turns: u64,
+    /// Number of games won with each score
+    pub scores: BTreeMap<i32, u64>,
+}
+
+impl BoardStats {
+    fn new(board: i32) -> BoardStats {
+        BoardStats {
+            board,
+            trials: 0,
+            won: 0,
+            first: 0,
+            last: 0,
+            turns: 0,
+            scores: BTreeMap::new(),
+        }
+    }
+
+    fn merge(&mut self, other: &BoardStats) {
+        self.trials += other.trials;
+        self.won += other.won;
+        self.first += other.first;
+        self.last += other.last;
+        self.turns += other.turns;
+        for (score, n) in &other.scores {
+            *self.scores.entry(*score).or_insert(0) += n;
+        }
+    }
+
+    /// Share of the games won first, or `None` without any game
+    pub fn first_probability(&self) -> Option<f64> {
+        if self.trials == 0 {
+            None
+        } else {
+            Some(self.first as f64 / self.trials as f64)
+        }
+    }
+
+    /// Share of the games won last, the games in which some board never
+    /// wins having no last winner, or `None` without any game
+    pub fn last_probability(&self) -> Option<f64> {
+        if self.trials == 0 {
+            None
+        } else {
+            Some(self.last as f64 / self.trials as f64)
+        }
+    }
+
+    /// Expected number of draws for the board to win, over the games it won
+    pub fn expected_turn(&self) -> Option<f64> {
+        if self.won == 0 {
+            None
+        } else {
+            Some(self.turns as f64 / self.won as f64)
+        }
+    }
+
+    pub fn mean_score(&self) -> Option<f64> {
+        if self.won == 0 {
+            return None;
+        }
+        let total: f64 = self.scores.iter().map(|(s, n)| *s as f64 * *n as f64).sum();
+        Some(total / self.won as f64)
+    }
+}
+
+fn simulate_range(bingo: &Bingo, seed: u64, trials: &Range<u64>) -> Vec<BoardStats> {
+    let mut stats: Vec<BoardStats> = bingo
+        .boards()
+        .iter()
+        .map(|b| BoardStats::new(b.num()))
+        .collect();
+    let positions: HashMap<i32, usize> = bingo
+        .boards()
+        .iter()
+        .enumerate()
+        .map(|(i, b)| (b.num(), i))
+        .collect();
+    let position = |num: i32| positions[&num];
+    let mut draws = bingo.draw().to_vec();
+    for trial in trials.clone() {
+        draws.copy_from_slice(bingo.draw());
+        Rng::new(seed.wrapping_add(trial)).shuffle(&mut draws);
+        let res = play_draws(bingo, &draws);
+        for win in &res.wins {
+            let s = &mut stats[position(win.board)];
+            s.won += 1;
+            s.turns += win.turn as u64 + 1;
+            *s.scores.entry(win.score).or_insert(0) += 1;
+        }
+        if let Some(win) = res.first() {
+            stats[position(win.board)].first += 1;
+        }
+        if res.wins.len() == stats.len() {
+            if let Some(win) = res.last() {
+                stats[position(win.board)].last += 1;
+            }
+        }
+    }
+    for s in stats.iter_mut() {
+        s.trials = trials.end - trials.start;
+    }
+    stats
+}
+
+/// Play `trials` games, each with the numbers drawn in a random order
+/// derived from `seed` and the trial number, and gather statistics per
+/// board (in board order). The result only depends on the seed, not on the
+/// number of `jobs` running trials in parallel.
+pub fn simulate(bingo: &Bingo, trials: u64, seed: u64, jobs: usize) -> Vec<BoardStats> {
+    let jobs = jobs.max(1) as u64;
+    let chunk = trials.div_ceil(jobs).max(1);
+    let ranges: Vec<Range<u64>> = (0..trials)
+        .step_by(chunk as usize)
+        .map(|start| start..(start + chunk).min(trials))
+        .collect();
+    let partials = par_map(&ranges, jobs as usize, |r| simulate_range(bingo, seed, r));
+    let mut stats: Vec<BoardStats> = bingo
+        .boards()
+        .iter()
+        .map(|b| BoardStats::new(b.num()))
+        .collect();
+    for partial in partials {
+        for (s, p) in stats.iter_mut().zip(partial.iter()) {
+            s.merge(p);
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::y2021::bingo::{sample, Board};
+
+    #[test]
+    fn probabilities_sum_to_one() {
+        let stats = simulate(&sample(), 200, 1, 1);
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats.iter().map(|s| s.first).sum::<u64>(), 200);
+        assert_eq!(stats.iter().map(|s| s.last).sum::<u64>(), 200);
+        for s in &stats {
+            // every number is eventually drawn, so every board wins
+            assert_eq!(s.won, 200);
+            assert_eq!(s.scores.values().sum::<u64>(), 200);
+            let turn = s.expected_turn().unwrap();
+            assert!((5.0..=27.0).contains(&turn));
+        }
+    }
+
+    #[test]
+    fn simulation_is_reproducible_whatever_the_jobs() {
+        let bingo = sample();
+
+        assert_eq!(simulate(&bingo, 50, 7, 1), simulate(&bingo, 50, 7, 3));
+        assert_ne!(simulate(&bingo, 50, 7, 1), simulate(&bingo, 50, 8, 1));
+    }
+
+    #[test]
+    fn board_made_of_early_numbers_always_wins_first() {
+        let good = Board::new(0, vec![vec![1, 2], vec![3, 4]]).unwrap();
+        let bad = Board::new(1, vec![vec![1, 5], vec![6, 7]]).unwrap();
+        // board 0 wins as soon as 1 and 2, or 1 and 3, etc. are drawn
+        let bingo = Bingo::new(vec![1, 2, 3, 4], vec![good, bad]);
+
+        let stats = simulate(&bingo, 20, 0, 2);
+
+        assert_eq!(stats[0].first_probability(), Some(1.0));
+        assert_eq!(stats[1].won, 0);
+        assert_eq!(stats[1].expected_turn(), None);
+        // board 1 never wins, so no board is the last to win
+        assert_eq!(stats[0].last_probability(), Some(0.0));
+        assert_eq!(stats[1].last_probability(), Some(0.0));
+    }
+
+    #[test]
+    fn no_probability_without_games() {
+        let stats = simulate(&sample(), 0, 1, 2);
+
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[0].first_probability(), None);
+        assert_eq!(stats[0].last_probability(), None);
+        assert_eq!(stats[0].expected_turn(), None);
+    }
+}
@@ -0,0 +1,390 @@
+//! Search for draw orders making some bingo board win first or last.
+//!
+//! A board only reaches a goal when it wins alone on its draw, so that the
+//! answers do not depend on the tie-break policy.
+use crate::budget::{Budget, Exhausted};
+use crate::y2021::bingo::Bingo;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    /// The board wins before any other one
+    WinFirst(i32),
+    /// Every other board wins before the board does
+    WinLast(i32),
+}
+
+/// Why a goal cannot be reached, whatever the draw order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Proof {
+    UnknownBoard(i32),
+    /// Some board needed by the goal has no line made of drawn numbers only
+    Unwinnable(i32),
+    /// Every line the board could win with contains a whole line of another
+    /// board, given as (numbers of the line, other board)
+    Blocked(Vec<(Vec<i32>, i32)>),
+    /// Every way to make all other boards win also completes a line of the
+    /// board
+    NoOrder,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    /// The shortest sequence of draws reaching the goal. The remaining
+    /// numbers can follow in any order.
+    Found(Vec<i32>),
+    Impossible(Proof),
+}
+
+/// The distinct drawn numbers, and the lines of each board as indices of
+/// these numbers, keeping only the lines made of drawn numbers
+struct Lines {
+    numbers: Vec<i32>,
+    boards: Vec<Vec<Vec<usize>>>,
+}
+
+impl Lines {
+    fn new(bingo: &Bingo) -> Lines {
+        let mut numbers = vec![];
+        let mut index = HashMap::new();
+        for n in bingo.draw() {
+            index.entry(*n).or_insert_with(|| {
+                numbers.push(*n);
+                numbers.len() - 1
+            });
+        }
+        let boards = (0..bingo.boards().len())
+            .map(|b| {
+                bingo
+                    .line_numbers(b)
+                    .iter()
+                    .filter_map(|line| {
+                        let mut line: Vec<usize> = line
+                            .iter()
+                            .map(|n| index.get(n).copied())
+                            .collect::<Option<_>>()?;
+                        line.sort_unstable();
+                        line.dedup();
+                        Some(line)
+                    })
+                    .collect()
+            })
+            .collect();
+        Lines { numbers, boards }
+    }
+
+    fn to_numbers(&self, line: &[usize]) -> Vec<i32> {
+        line.iter().map(|n| self.numbers[*n]).collect()
+    }
+}
+
+fn contains(outer: &[usize], inner: &[usize]) -> bool {
+    inner.iter().all(|n| outer.contains(n))
+}
+
+/// Find the shortest draw sequence reaching some goal, or prove there is
+/// none. Only winning last needs a search, which expands one node per line
+/// tried and stops when the budget is exhausted.
+pub fn search(bingo: &Bingo, goal: Goal, budget: &mut Budget) -> Result<Outcome, Exhausted> {
+    let num = match goal {
+        Goal::WinFirst(num) | Goal::WinLast(num) => num,
+    };
+    let target = match bingo.boards().iter().position(|b| b.num() == num) {
+        Some(target) => target,
+        None => return Ok(Outcome::Impossible(Proof::UnknownBoard(num))),
+    };
+    let lines = Lines::new(bingo);
+    if lines.boards[target].is_empty() {
+        return Ok(Outcome::Impossible(Proof::Unwinnable(num)));
+    }
+    match goal {
+        Goal::WinFirst(_) => Ok(win_first(bingo, &lines, target)),
+        Goal::WinLast(_) => win_last(bingo, &lines, target, budget),
+    }
+}
+
+/// Drawing numbers which are not on a line of the target only lets other
+/// boards win earlier, so the target wins first with the shortest of its
+/// lines which contains no whole line of another board
+fn win_first(bingo: &Bingo, lines: &Lines, target: usize) -> Outcome {
+    let mut blocked = vec![];
+    let mut best: Option<&Vec<usize>> = None;
+    for line in &lines.boards[target] {
+        let blocker = lines
+            .boards
+            .iter()
+            .enumerate()
+            .filter(|(b, _)| *b != target)
+            .find(|(_, other)| other.iter().any(|l| contains(line, l)));
+        match blocker {
+            Some((b, _)) => blocked.push((lines.to_numbers(line), bingo.boards()[b].num())),
+            None => {
+                if best.is_none_or(|best| line.len() < best.len()) {
+                    best = Some(line);
+                }
+            }
+        }
+    }
+    match best {
+        Some(line) => Outcome::Found(lines.to_numbers(line)),
+        None => Outcome::Impossible(Proof::Blocked(blocked)),
+    }
+}
+
+/// Choose a line for every other board, so that all these lines together do
+/// not contain a line of the target, then finish a line of the target.
+/// Branch and bound on the number of draws.
+struct LastSearch<'a> {
+    target: &'a [Vec<usize>],
+    others: Vec<&'a [Vec<usize>]>,
+    /// Number of chosen lines using each number
+    counts: Vec<u32>,
+    /// Numbers used by chosen lines, in the order they were added
+    order: Vec<usize>,
+    best: Option<Vec<usize>>,
+}
+
+impl<'a> LastSearch<'a> {
+    fn missing(&self, line: &[usize]) -> usize {
+        line.iter().filter(|n| self.counts[**n] == 0).count()
+    }
+
+    fn min_missing(&self, lines: &[Vec<usize>]) -> usize {
+        lines.iter().map(|l| self.missing(l)).min().unwrap_or(0)
+    }
+
+    fn completes_target(&self) -> bool {
+        self.target.iter().any(|l| self.missing(l) == 0)
+    }
+
+    fn add(&mut self, line: &[usize]) {
+        for n in line {
+            if self.counts[*n] == 0 {
+                self.order.push(*n);
+            }
+            self.counts[*n] += 1;
+        }
+    }
+
+    fn remove(&mut self, line: &[usize]) {
+        for n in line {
+            self.counts[*n] -= 1;
+            if self.counts[*n] == 0 {
+                self.order.pop();
+            }
+        }
+    }
+
+    fn dfs(&mut self, i: usize, budget: &mut Budget) -> Result<(), Exhausted> {
+        budget.tick(self.others.len() - i)?;
+        let best = self.best.as_ref().map_or(usize::MAX, |b| b.len());
+        if i == self.others.len() {
+            let finish = self.target.iter().min_by_key(|l| self.missing(l)).unwrap();
+            if self.order.len() + self.missing(finish) < best {
+                let mut order = self.order.clone();
+                order.extend(finish.iter().filter(|n| self.counts[**n] == 0));
+                budget.best(order.len() as i64);
+                self.best = Some(order);
+            }
+            return Ok(());
+        }
+        // every remaining board needs at least its cheapest line, and the
+        // target at least one more number
+        let needed = self.others[i..]
+            .iter()
+            .map(|lines| self.min_missing(lines))
+            .max()
+            .unwrap_or(0);
+        if self.order.len() + needed + 1 >= best {
+            return Ok(());
+        }
+        let lines = self.others[i];
+        if lines.iter().any(|l| self.missing(l) == 0) {
+            return self.dfs(i + 1, budget);
+        }
+        let mut candidates: Vec<&Vec<usize>> = lines.iter().collect();
+        candidates.sort_by_key(|l| self.missing(l));
+        for line in candidates {
+            self.add(line);
+            if !self.completes_target() {
+                let res = self.dfs(i + 1, budget);
+                if res.is_err() {
+                    self.remove(line);
+                    return res;
+                }
+            }
+            self.remove(line);
+        }
+        Ok(())
+    }
+}
+
+fn win_last(
+    bingo: &Bingo,
+    lines: &Lines,
+    target: usize,
+    budget: &mut Budget,
+) -> Result<Outcome, Exhausted> {
+    let mut others = vec![];
+    for (b, board_lines) in lines.boards.iter().enumerate() {
+        if b == target {
+            continue;
+        }
+        if board_lines.is_empty() {
+            let num = bingo.boards()[b].num();
+            return Ok(Outcome::Impossible(Proof::Unwinnable(num)));
+        }
+        others.push(board_lines.as_slice());
+    }
+    // boards with fewer choices first, to prune early
+    others.sort_by_key(|lines| lines.len());
+    let mut search = LastSearch {
+        target: &lines.boards[target],
+        others,
+        counts: vec![0; lines.numbers.len()],
+        order: vec![],
+        best: None,
+    };
+    search.dfs(0, budget)?;
+    Ok(match search.best {
+        Some(order) => Outcome::Found(lines.to_numbers(&order)),
+        None => Outcome::Impossible(Proof::NoOrder),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::y2021::bingo::{play_draws, sample, Board};
+
+    fn bingo(draw: Vec<i32>, boards: Vec<Vec<Vec<i32>>>) -> Bingo {
+        let boards = boards
+            .into_iter()
+            .enumerate()
+            .map(|(i, rows)| Board::new(i as i32, rows).unwrap())
+            .collect();
+        Bingo::new(draw, boards)
+    }
+
+    fn found(outcome: Outcome) -> Vec<i32> {
+        match outcome {
+            Outcome::Found(order) => order,
+            other => panic!("no order found: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn make_any_sample_board_win_first() {
+        let bingo = sample();
+        for num in 0..3 {
+            let order =
+                found(search(&bingo, Goal::WinFirst(num), &mut Budget::unlimited()).unwrap());
+
+            let res = play_draws(&bingo, &order);
+            assert_eq!(order.len(), 5);
+            assert_eq!(res.first_winners().len(), 1);
+            assert_eq!(res.first().map(|w| (w.board, w.turn)), Some((num, 4)));
+        }
+    }
+
+    #[test]
+    fn make_sample_board_win_last() {
+        let bingo = sample();
+        for num in 0..3 {
+            let order =
+                found(search(&bingo, Goal::WinLast(num), &mut Budget::unlimited()).unwrap());
+
+            let res = play_draws(&bingo, &order);
+            assert_eq!(res.wins.len(), 3);
+            assert_eq!(res.last_winners().len(), 1);
+            assert_eq!(
+                res.last().map(|w| (w.board, w.turn + 1)),
+                Some((num, order.len()))
+            );
+        }
+    }
+
+    #[test]
+    fn win_last_finds_the_shortest_order() {
+        // boards 1 and 2 both win with 5 alone, or with 3 and 4
+        let game = bingo(
+            vec![1, 2, 3, 4, 5, 6],
+            vec![
+                vec![vec![1, 2], vec![6, 9]],
+                vec![vec![3, 5]],
+                vec![vec![5, 4]],
+            ],
+        );
+
+        let order = found(search(&game, Goal::WinLast(0), &mut Budget::unlimited()).unwrap());
+
+        assert_eq!(order, vec![5, 1, 2]);
+    }
+
+    #[test]
+    fn prove_board_cannot_win_first() {
+        let game = bingo(
+            vec![1, 2, 3, 4],
+            vec![
+                vec![vec![1, 2], vec![3, 4]],
+                vec![vec![1, 2]],
+                vec![vec![3, 4]],
+            ],
+        );
+
+        match search(&game, Goal::WinFirst(0), &mut Budget::unlimited()) {
+            Ok(Outcome::Impossible(Proof::Blocked(lines))) => {
+                assert_eq!(lines.len(), 4);
+                assert_eq!(lines[0], (vec![1, 2], 1));
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(
+            found(search(&game, Goal::WinFirst(1), &mut Budget::unlimited()).unwrap()),
+            vec![1]
+        );
+    }
+
+    #[test]
+    fn prove_board_cannot_win_last() {
+        let game = bingo(vec![1, 2, 3], vec![vec![vec![1, 2]], vec![vec![1]]]);
+        assert_eq!(
+            search(&game, Goal::WinLast(0), &mut Budget::unlimited()),
+            Ok(Outcome::Impossible(Proof::NoOrder))
+        );
+        assert_eq!(
+            found(search(&game, Goal::WinFirst(0), &mut Budget::unlimited()).unwrap()),
+            vec![2]
+        );
+
+        let game = bingo(vec![1, 2], vec![vec![vec![1, 2]], vec![vec![7, 8]]]);
+        assert_eq!(
+            search(&game, Goal::WinLast(0), &mut Budget::unlimited()),
+            Ok(Outcome::Impossible(Proof::Unwinnable(1)))
+        );
+        assert_eq!(
+            search(&game, Goal::WinFirst(1), &mut Budget::unlimited()),
+            Ok(Outcome::Impossible(Proof::Unwinnable(1)))
+        );
+        assert_eq!(
+            search(&game, Goal::WinFirst(5), &mut Budget::unlimited()),
+            Ok(Outcome::Impossible(Proof::UnknownBoard(5)))
+        );
+    }
+
+    #[test]
+    fn stop_when_budget_is_exhausted() {
+        let mut unlimited = Budget::unlimited();
+        let found = search(&sample(), Goal::WinLast(1), &mut unlimited);
+        let needed = unlimited.progress().expanded;
+        assert!(needed > 1);
+
+        let mut budget = Budget::unlimited().with_max_iterations(needed);
+        assert_eq!(search(&sample(), Goal::WinLast(1), &mut budget), found);
+        let mut budget = Budget::unlimited().with_max_iterations(needed - 1);
+        assert_eq!(
+            search(&sample(), Goal::WinLast(1), &mut budget),
+            Err(Exhausted::Iterations)
+        );
+    }
+}
@@ -0,0 +1,500 @@
+//! Aligning crabs on a common position for the least fuel, with a fuel cost
+//! depending on the distance each crab moves
+use crate::nums::sum_of_n;
+
+/// Fuel burnt by a crab moving some distance
+pub trait CostFn {
+    /// Fuel for a distance, which must not decrease with the distance
+    fn cost(&self, distance: i64) -> i64;
+
+    /// Whether the extra fuel of each step never decreases with the
+    /// distance, making the total fuel convex in the target position
+    fn convex(&self) -> bool {
+        true
+    }
+
+    /// Targets among which the best one is, if they can be found from the
+    /// sorted positions without searching
+    fn candidates(&self, _sorted: &[i64]) -> Option<Vec<i64>> {
+        None
+    }
+
+    /// `(square, linear, divisor)` when the fuel for a distance `d` is
+    /// `(square * d * d + linear * d) / divisor`, so that the fuel of many
+    /// crabs follows from the sums of their positions and of their squares
+    fn quadratic_form(&self) -> Option<(i64, i64, i64)> {
+        None
+    }
+}
+
+/// One unit of fuel per step
+pub struct Linear;
+
+/// One more unit of fuel for each step than for the previous one
+pub struct Triangular;
+
+/// The square of the distance
+pub struct Quadratic;
+
+/// Any cost, searched for by bisecting on the change of fuel when convex, and
+/// by trying every target between the crabs otherwise
+pub struct Custom<F> {
+    f: F,
+    convex: bool,
+}
+
+impl<F: Fn(i64) -> i64> Custom<F> {
+    pub fn convex(f: F) -> Custom<F> {
+        Custom { f, convex: true }
+    }
+
+    pub fn any(f: F) -> Custom<F> {
+        Custom { f, convex: false }
+    }
+}
+
+/// Mean of sorted positions, rounded down
+fn mean(sorted: &[i64]) -> i64 {
+    let sum: i128 = sorted.iter().map(|p| i128::from(*p)).sum();
+    sum.div_euclid(sorted.len() as i128) as i64
+}
+
+impl CostFn for Linear {
+    fn cost(&self, distance: i64) -> i64 {
+        distance
+    }
+
+    /// Any median is optimal
+    fn candidates(&self, sorted: &[i64]) -> Option<Vec<i64>> {
+        Some(vec![sorted[(sorted.len() - 1) / 2]])
+    }
+
+    fn quadratic_form(&self) -> Option<(i64, i64, i64)> {
+        Some((0, 1, 1))
+    }
+}
+
+impl CostFn for Triangular {
+    fn cost(&self, distance: i64) -> i64 {
+        sum_of_n(distance)
+    }
+
+    /// The real optimum is within half a step of the mean
+    fn candidates(&self, sorted: &[i64]) -> Option<Vec<i64>> {
+        let m = mean(sorted);
+        Some((m - 1..=m + 2).collect())
+    }
+
+    fn quadratic_form(&self) -> Option<(i64, i64, i64)> {
+        Some((1, 1, 2))
+    }
+}
+
+impl CostFn for Quadratic {
+    fn cost(&self, distance: i64) -> i64 {
+        distance * distance
+    }
+
+    /// The real optimum is the mean
+    fn candidates(&self, sorted: &[i64]) -> Option<Vec<i64>> {
+        let m = mean(sorted);
+        Some(vec![m, m + 1])
+    }
+
+    fn quadratic_form(&self) -> Option<(i64, i64, i64)> {
+        Some((1, 0, 1))
+    }
+}
+
+impl<F: Fn(i64) -> i64> CostFn for Custom<F> {
+    fn cost(&self, distance: i64) -> i64 {
+        (self.f)(distance)
+    }
+
+    fn convex(&self) -> bool {
+        self.convex
+    }
+}
+
+/// Where the crabs gather, and the fuel they burn to get there
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Alignment {
+    pub position: i64,
+    pub fuel: i64,
+}
+
+/// Total fuel for all the crabs to reach a target
+pub fn fuel<C: CostFn + ?Sized>(positions: &[i64], target: i64, cost: &C) -> i64 {
+    positions
+        .iter()
+        .map(|p| cost.cost((p - target).abs()))
+        .sum()
+}
+
+/// The target needing the least fuel, the leftmost one on ties, or `None`
+/// without any crab
+pub fn solve<C: CostFn + ?Sized>(positions: &[i64], cost: &C) -> Option<Alignment> {
+    let mut sorted = positions.to_vec();
+    sorted.sort_unstable();
+    solve_sorted(&sorted, cost)
+}
+
+/// Same as `solve`, on positions already sorted
+pub fn solve_sorted<C: CostFn + ?Sized>(sorted: &[i64], cost: &C) -> Option<Alignment> {
+    let (lo, hi) = (*sorted.first()?, *sorted.last()?);
+    let at = |position| Alignment {
+        position,
+        fuel: fuel(sorted, position, cost),
+    };
+    // moving a target outside of the crabs towards them never costs more
+    let best = if let Some(candidates) = cost.candidates(sorted) {
+        candidates
+            .into_iter()
+            .map(|p| at(p.max(lo).min(hi)))
+            .min_by_key(|a| (a.fuel, a.position))?
+    } else if cost.convex() {
+        // leftmost target from which moving right does not save fuel
+        let (mut from, mut to) = (lo, hi);
+        while from < to {
+            let mid = from + (to - from) / 2;
+            if at(mid).fuel <= at(mid + 1).fuel {
+                to = mid;
+            } else {
+                from = mid + 1;
+            }
+        }
+        at(from)
+    } else {
+        (lo..=hi).map(at).min_by_key(|a| a.fuel)?
+    };
+    Some(best)
+}
+
+/// Crabs gathering on several positions, each crab going to one of them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Gathering {
+    /// Meeting points from left to right, with the fuel of their crabs
+    pub points: Vec<Alignment>,
+    /// Index in `points` of the meeting point of each crab
+    pub assignment: Vec<usize>,
+    pub fuel: i64,
+}
+
+/// Fuel of the runs of sorted crabs, read from prefix sums in logarithmic
+/// time when the cost has a quadratic form
+struct Runs<'a, C: ?Sized> {
+    sorted: &'a [i64],
+    cost: &'a C,
+    form: Option<(i64, i64, i64)>,
+    // sums[i], squares[i]: sum of the first i positions and of their squares
+    sums: Vec<i128>,
+    squares: Vec<i128>,
+}
+
+impl<'a, C: CostFn + ?Sized> Runs<'a, C> {
+    fn new(sorted: &'a [i64], cost: &'a C) -> Runs<'a, C> {
+        let mut sums = vec![0];
+        let mut squares = vec![0];
+        for p in sorted {
+            let p = i128::from(*p);
+            sums.push(sums.last().unwrap() + p);
+            squares.push(squares.last().unwrap() + p * p);
+        }
+        Runs {
+            sorted,
+            cost,
+            form: cost.quadratic_form(),
+            sums,
+            squares,
+        }
+    }
+
+    /// Fuel for the crabs from i to j excluded to reach a target
+    fn fuel(&self, i: usize, j: usize, target: i64) -> i64 {
+        let (square, linear, divisor) = match self.form {
+            Some(form) => form,
+            None => return fuel(&self.sorted[i..j], target, self.cost),
+        };
+        // crabs from i to m excluded are left of the target
+        let m = i + self.sorted[i..j].partition_point(|p| *p < target);
+        let t = i128::from(target);
+        let (below, above) = (self.sums[m] - self.sums[i], self.sums[j] - self.sums[m]);
+        let distances = t * (m - i) as i128 - below + above - t * (j - m) as i128;
+        let squares =
+            self.squares[j] - self.squares[i] - 2 * t * (below + above) + t * t * (j - i) as i128;
+        ((i128::from(square) * squares + i128::from(linear) * distances) / i128::from(divisor))
+            as i64
+    }
+
+    /// Best alignment of every run starting at i, by increasing end.
+    ///
+    /// The leftmost best target of a convex cost never moves left when a
+    /// crab joins on the right, so it is searched for from the previous one,
+    /// by doubling then halving the step.
+    fn from(&self, i: usize) -> Vec<Alignment> {
+        let mut target = self.sorted[i];
+        (i + 1..=self.sorted.len())
+            .map(|j| {
+                let hi = self.sorted[j - 1];
+                let saves = |t: i64| self.fuel(i, j, t) > self.fuel(i, j, t + 1);
+                // the best target is within from..=to
+                let (mut from, mut to, mut step) = (target, target, 1);
+                while to < hi && saves(to) {
+                    from = to + 1;
+                    to = (to + step).min(hi);
+                    step *= 2;
+                }
+                while from < to {
+                    let mid = from + (to - from) / 2;
+                    if saves(mid) {
+                        from = mid + 1;
+                    } else {
+                        to = mid;
+                    }
+                }
+                target = from;
+                Alignment {
+                    position: target,
+                    fuel: self.fuel(i, j, target),
+                }
+            })
+            .collect()
+    }
+}
+
+/// The least fuel for the crabs to gather on at most `k` positions, using
+/// as few of them as possible, or `None` without crabs or meeting points, or
+/// for a cost which is not convex.
+///
+/// With a convex cost, crabs going to the same point are neighbours once
+/// sorted, so the best alignment of every run of sorted crabs is computed,
+/// then combined with a dynamic programming over the number of runs.
+pub fn solve_k<C: CostFn + ?Sized>(positions: &[i64], k: usize, cost: &C) -> Option<Gathering> {
+    let n = positions.len();
+    if n == 0 || k == 0 || !cost.convex() {
+        return None;
+    }
+    let mut order: Vec<usize> = (0..n).collect();
+    order.sort_by_key(|i| positions[*i]);
+    let sorted: Vec<i64> = order.iter().map(|i| positions[*i]).collect();
+
+    // runs[i][j - i - 1]: best alignment of the crabs from i to j excluded
+    let fuels = Runs::new(&sorted, cost);
+    let runs: Vec<Vec<Alignment>> = (0..n).map(|i| fuels.from(i)).collect();
+    let run = |i: usize, j: usize| runs[i][j - i - 1];
+
+    // fuel[g][j]: least fuel for the first j crabs in g runs, with the start
+    // of the last run
+    let groups = k.min(n);
+    let mut best: Vec<Vec<Option<(i64, usize)>>> = vec![vec![None; n + 1]; groups + 1];
+    best[0][0] = Some((0, 0));
+    for g in 1..=groups {
+        for j in g..=n {
+            best[g][j] = (g - 1..j)
+                .filter_map(|i| best[g - 1][i].map(|(f, _)| (f + run(i, j).fuel, i)))
+                .min_by_key(|(f, i)| (*f, std::cmp::Reverse(*i)));
+        }
+    }
+    let (used, fuel) = (1..=groups)
+        .filter_map(|g| best[g][n].map(|(f, _)| (g, f)))
+        .min_by_key(|(g, f)| (*f, *g))?;
+
+    let mut points = vec![];
+    let mut assignment = vec![0; n];
+    let mut j = n;
+    for g in (1..=used).rev() {
+        let (_, i) = best[g][j]?;
+        points.push(run(i, j));
+        for crab in &order[i..j] {
+            assignment[*crab] = g - 1;
+        }
+        j = i;
+    }
+    points.reverse();
+    Some(Gathering {
+        points,
+        assignment,
+        fuel,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Rng;
+
+    const SAMPLE: [i64; 10] = [16, 1, 2, 0, 4, 2, 7, 1, 2, 14];
+
+    fn exhaustive<C: CostFn>(positions: &[i64], cost: &C) -> Alignment {
+        let (lo, hi) = (
+            *positions.iter().min().unwrap(),
+            *positions.iter().max().unwrap(),
+        );
+        (lo..=hi)
+            .map(|position| Alignment {
+                position,
+                fuel: fuel(positions, position, cost),
+            })
+            .min_by_key(|a| a.fuel)
+            .unwrap()
+    }
+
+    #[test]
+    fn sample_costs() {
+        let linear = solve(&SAMPLE, &Linear).unwrap();
+        assert_eq!(
+            linear,
+            Alignment {
+                position: 2,
+                fuel: 37
+            }
+        );
+        let triangular = solve(&SAMPLE, &Triangular).unwrap();
+        assert_eq!(
+            triangular,
+            Alignment {
+                position: 5,
+                fuel: 168
+            }
+        );
+        assert_eq!(solve(&SAMPLE, &Quadratic).unwrap().position, 5);
+        assert_eq!(solve(&[], &Linear), None);
+    }
+
+    #[test]
+    fn custom_costs() {
+        let cubic = Custom::convex(|d| d * d * d);
+        assert_eq!(solve(&SAMPLE, &cubic), Some(exhaustive(&SAMPLE, &cubic)));
+        // never more than 3 units of fuel: gathering in the middle is worse
+        let capped = Custom::any(|d| d.min(3));
+        let best = solve(&[0, 1, 4, 5], &capped).unwrap();
+        assert_eq!(
+            best,
+            Alignment {
+                position: 0,
+                fuel: 7
+            }
+        );
+        assert_eq!(fuel(&[0, 1, 4, 5], 2, &capped), 8);
+    }
+
+    #[test]
+    fn one_meeting_point_is_the_single_alignment() {
+        let mut rng = Rng::new(11);
+        for _ in 0..50 {
+            let n = rng.range(1, 20) as usize;
+            let positions: Vec<i64> = (0..n).map(|_| rng.range(-30, 30)).collect();
+            let costs: [&dyn CostFn; 3] = [&Linear, &Triangular, &Quadratic];
+            for cost in costs.iter() {
+                let single = solve(&positions, *cost).unwrap();
+                let gathering = solve_k(&positions, 1, *cost).unwrap();
+                assert_eq!(gathering.points, [single]);
+                assert_eq!(gathering.fuel, single.fuel);
+                assert_eq!(gathering.assignment, vec![0; n]);
+            }
+        }
+        assert_eq!(solve_k(&SAMPLE, 0, &Linear), None);
+        assert_eq!(solve_k(&[], 2, &Linear), None);
+    }
+
+    #[test]
+    fn gather_on_several_points() {
+        let gathering = solve_k(&SAMPLE, 2, &Linear).unwrap();
+        assert_eq!(
+            gathering.points,
+            [
+                Alignment {
+                    position: 2,
+                    fuel: 11
+                },
+                Alignment {
+                    position: 14,
+                    fuel: 2
+                }
+            ]
+        );
+        assert_eq!(gathering.assignment, [1, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(gathering.fuel, 13);
+
+        // as many points as distinct positions, but no more
+        let gathering = solve_k(&[3, 8, 3, 8], 5, &Triangular).unwrap();
+        assert_eq!(gathering.fuel, 0);
+        assert_eq!(gathering.points.len(), 2);
+        assert_eq!(gathering.assignment, [0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn same_fuel_as_trying_every_partition() {
+        let mut rng = Rng::new(5);
+        for _ in 0..30 {
+            let n = rng.range(1, 9) as usize;
+            let positions: Vec<i64> = (0..n).map(|_| rng.range(-20, 20)).collect();
+            for k in 1..4_usize {
+                // every assignment of the crabs to k labels
+                let mut expected = i64::MAX;
+                for code in 0..k.pow(n as u32) {
+                    let mut groups = vec![vec![]; k];
+                    for (i, p) in positions.iter().enumerate() {
+                        groups[code / k.pow(i as u32) % k].push(*p);
+                    }
+                    let total = groups
+                        .iter()
+                        .filter_map(|g| solve(g, &Triangular))
+                        .map(|a| a.fuel)
+                        .sum();
+                    expected = expected.min(total);
+                }
+                let gathering = solve_k(&positions, k, &Triangular).unwrap();
+                assert_eq!(gathering.fuel, expected, "{:?} in {}", positions, k);
+                let total: i64 = positions
+                    .iter()
+                    .zip(&gathering.assignment)
+                    .map(|(p, g)| Triangular.cost((p - gathering.points[*g].position).abs()))
+                    .sum();
+                assert_eq!(total, gathering.fuel);
+            }
+        }
+    }
+
+    #[test]
+    fn run_fuel_from_sums_matches_direct_fuel() {
+        let mut rng = Rng::new(13);
+        let positions: Vec<i64> = (0..200).map(|_| rng.range(-1000, 1000)).collect();
+        let by_steps = Custom::convex(sum_of_n);
+        for k in 1..5 {
+            let fast = solve_k(&positions, k, &Triangular).unwrap();
+            assert_eq!(solve_k(&positions, k, &by_steps).unwrap(), fast);
+        }
+        let quadratic = Custom::convex(|d| d * d);
+        assert_eq!(
+            solve_k(&positions, 3, &Quadratic),
+            solve_k(&positions, 3, &quadratic)
+        );
+    }
+
+    #[test]
+    fn gathering_needs_a_convex_cost() {
+        assert_eq!(solve_k(&SAMPLE, 2, &Custom::any(|d| d.min(3))), None);
+    }
+
+    #[test]
+    fn same_fuel_as_trying_every_target() {
+        let mut rng = Rng::new(7);
+        for _ in 0..200 {
+            let n = rng.range(1, 30) as usize;
+            let positions: Vec<i64> = (0..n).map(|_| rng.range(-50, 50)).collect();
+            let costs: [&dyn CostFn; 4] = [
+                &Linear,
+                &Triangular,
+                &Quadratic,
+                &Custom::convex(|d| d * d * d + d),
+            ];
+            for cost in costs.iter() {
+                let best = solve(&positions, *cost).unwrap();
+                let expected = (-50..50).map(|t| fuel(&positions, t, *cost)).min().unwrap();
+                assert_eq!(best.fuel, expected, "{:?}", positions);
+                assert_eq!(fuel(&positions, best.position, *cost), best.fuel);
+            }
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use num::BigUint;
+use std::fmt;
+use std::fmt::Display;
+
+const WORD: usize = 64;
+
+/// A string of bits of any width, first bit being the most significant,
+/// packed into 64 bits words
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitString {
+    width: usize,
+    words: Vec<u64>,
+}
+
+impl BitString {
+    /// A bit string of `width` zeros
+    pub fn zeros(width: usize) -> BitString {
+        BitString {
+            width,
+            words: vec![0; width.div_ceil(WORD)],
+        }
+    }
+
+    /// Parse a non-empty string made of '0' and '1' only
+    pub fn parse(input: &str) -> Option<BitString> {
+        if input.is_empty() {
+            return None;
+        }
+        let mut res = BitString::zeros(input.len());
+        for (i, c) in input.chars().enumerate() {
+            match c {
+                '0' => {}
+                '1' => res.set(i, true),
+                _ => return None,
+            }
+        }
+        Some(res)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The bit at position `i`, counting from the most significant one
+    pub fn get(&self, i: usize) -> bool {
+        assert!(i < self.width, "bit {} out of {} bits", i, self.width);
+        self.words[i / WORD] >> (i % WORD) & 1 == 1
+    }
+
+    pub fn set(&mut self, i: usize, bit: bool) {
+        assert!(i < self.width, "bit {} out of {} bits", i, self.width);
+        let mask = 1 << (i % WORD);
+        if bit {
+            self.words[i / WORD] |= mask;
+        } else {
+            self.words[i / WORD] &= !mask;
+        }
+    }
+
+    /// Iterate over the bits, most significant first
+    pub fn bits(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.width).map(move |i| self.get(i))
+    }
+
+    /// The value of the bit string if it fits in 128 bits
+    pub fn to_u128(&self) -> Option<u128> {
+        self.bits().try_fold(0_u128, |acc, bit| {
+            acc.checked_mul(2).and_then(|v| v.checked_add(bit as u128))
+        })
+    }
+
+    pub fn to_biguint(&self) -> BigUint {
+        let digits: Vec<u8> = self.bits().map(|b| b as u8).collect();
+        BigUint::from_radix_be(&digits, 2).unwrap_or_default()
+    }
+}
+
+impl Display for BitString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for bit in self.bits() {
+            write!(f, "{}", bit as u8)?;
+        }
+        Ok(())
+    }
+}
+
+/// A diagnostic report which cannot be analysed, lines and columns starting
+/// at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReportError {
+    /// The column is the first one which is not a bit
+    InvalidLine { line: usize, column: usize },
+    /// The column is the first one after the bits of the shortest line
+    InconsistentWidth {
+        line: usize,
+        column: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+
+impl Display for ReportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReportError::InvalidLine { line, .. } => write!(f, "line {} is not binary", line),
+            ReportError::InconsistentWidth {
+                line,
+                expected,
+                found,
+                ..
+            } => write!(f, "line {} has {} bits, expected {}", line, found, expected),
+        }
+    }
+}
+
+/// Parse every non-empty line of a report, which must all have the same width
+pub fn parse_report(input: &str) -> Result<Vec<BitString>, ReportError> {
+    let mut report: Vec<BitString> = vec![];
+    for (i, raw) in input.split('\n').enumerate() {
+        let line = raw.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let blanks = raw.len() - raw.trim_start().len();
+        let bits = BitString::parse(line).ok_or_else(|| ReportError::InvalidLine {
+            line: i + 1,
+            column: blanks + line.chars().take_while(|c| *c == '0' || *c == '1').count() + 1,
+        })?;
+        if let Some(first) = report.first() {
+            if first.width() != bits.width() {
+                return Err(ReportError::InconsistentWidth {
+                    line: i + 1,
+                    column: blanks + first.width().min(bits.width()) + 1,
+                    expected: first.width(),
+                    found: bits.width(),
+                });
+            }
+        }
+        report.push(bits);
+    }
+    Ok(report)
+}
+
+/// Number of ones at position `pos` among some numbers
+fn count_ones(report: &[BitString], pos: usize) -> usize {
+    report.iter().filter(|b| b.get(pos)).count()
+}
+
+/// Build a bit string of the report width, each bit chosen from the number
+/// of ones and zeros at that position
+fn from_counts(report: &[BitString], choose: fn(usize, usize) -> bool) -> BitString {
+    let width = report.first().map_or(0, |b| b.width());
+    let mut res = BitString::zeros(width);
+    for pos in 0..width {
+        let ones = count_ones(report, pos);
+        res.set(pos, choose(ones, report.len() - ones));
+    }
+    res
+}
+
+/// Most common bit at each position, 0 on ties
+pub fn gamma(report: &[BitString]) -> BitString {
+    from_counts(report, |ones, zeros| ones > zeros)
+}
+
+/// Least common bit at each position, 0 on ties
+pub fn epsilon(report: &[BitString]) -> BitString {
+    from_counts(report, |ones, zeros| ones < zeros)
+}
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    count: usize,
+    children: [Option<usize>; 2],
+}
+
+/// A binary trie of bit strings of the same width, counting the numbers
+/// below each node, to answer majority and minority queries on prefixes
+#[derive(Debug, Clone)]
+pub struct BitTrie {
+    width: usize,
+    nodes: Vec<Node>,
+}
+
+impl BitTrie {
+    pub fn new(width: usize) -> BitTrie {
+        BitTrie {
+            width,
+            nodes: vec![Node::default()],
+        }
+    }
+
+    /// Build a trie with every number of a report
+    pub fn from_report(report: &[BitString]) -> BitTrie {
+        let mut trie = BitTrie::new(report.first().map_or(0, |b| b.width()));
+        for bits in report {
+            trie.insert(bits);
+        }
+        trie
+    }
+
+    pub fn insert(&mut self, bits: &BitString) {
+        assert_eq!(bits.width(), self.width, "inconsistent width");
+        let mut node = 0;
+        self.nodes[node].count += 1;
+        for bit in bits.bits() {
+            node = match self.nodes[node].children[bit as usize] {
+                Some(child) => child,
+                None => {
+                    self.nodes.push(Node::default());
+                    let child = self.nodes.len() - 1;
+                    self.nodes[node].children[bit as usize] = Some(child);
+                    child
+                }
+            };
+            self.nodes[node].count += 1;
+        }
+    }
+
+    fn count_at(&self, node: Option<usize>) -> usize {
+        node.map_or(0, |n| self.nodes[n].count)
+    }
+
+    fn find(&self, prefix: &[bool]) -> Option<usize> {
+        prefix
+            .iter()
+            .try_fold(0, |node, bit| self.nodes[node].children[*bit as usize])
+    }
+
+    /// Number of inserted numbers starting with `prefix`
+    pub fn count(&self, prefix: &[bool]) -> usize {
+        self.count_at(self.find(prefix))
+    }
+
+    /// Number of ones and zeros following `prefix` among inserted numbers
+    pub fn ones_and_zeros(&self, prefix: &[bool]) -> (usize, usize) {
+        match self.find(prefix) {
+            Some(node) => {
+                let [zero, one] = self.nodes[node].children;
+                (self.count_at(one), self.count_at(zero))
+            }
+            None => (0, 0),
+        }
+    }
+
+    /// Walk from the root to a leaf, following at each node the bit chosen
+    /// by `keep` from the number of ones and zeros below it. A node with a
+    /// single non-empty child always leads to it, as a single number left
+    /// is always kept.
+    pub fn select(&self, keep: fn(usize, usize) -> bool) -> Option<BitString> {
+        if self.nodes[0].count == 0 {
+            return None;
+        }
+        let mut res = BitString::zeros(self.width);
+        let mut node = 0;
+        for pos in 0..self.width {
+            let [zero, one] = self.nodes[node].children;
+            let bit = match (zero, one) {
+                (Some(_), None) => false,
+                (None, Some(_)) => true,
+                _ => keep(self.count_at(one), self.count_at(zero)),
+            };
+            res.set(pos, bit);
+            node = self.nodes[node].children[bit as usize]?;
+        }
+        Some(res)
+    }
+
+    /// Follow the most common bit, ones on ties
+    pub fn oxygen_rating(&self) -> Option<BitString> {
+        self.select(|ones, zeros| ones >= zeros)
+    }
+
+    /// Follow the least common bit, zeros on ties
+    pub fn co2_rating(&self) -> Option<BitString> {
+        self.select(|ones, zeros| ones < zeros)
+    }
+}
+
+pub fn oxygen_rating(report: &[BitString]) -> Option<BitString> {
+    BitTrie::from_report(report).oxygen_rating()
+}
+
+pub fn co2_rating(report: &[BitString]) -> Option<BitString> {
+    BitTrie::from_report(report).co2_rating()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str =
+        "00100\n11110\n10110\n10111\n10101\n01111\n00111\n11100\n10000\n11001\n00010\n01010\n";
+
+    #[test]
+    fn analyse_sample_report() {
+        let report = parse_report(SAMPLE).unwrap();
+
+        assert_eq!(gamma(&report).to_u128(), Some(22));
+        assert_eq!(epsilon(&report).to_u128(), Some(9));
+        assert_eq!(oxygen_rating(&report).unwrap().to_string(), "10111");
+        assert_eq!(co2_rating(&report).unwrap().to_string(), "01010");
+    }
+
+    #[test]
+    fn report_invalid_and_inconsistent_lines() {
+        assert_eq!(
+            parse_report("0101\n\n011\n"),
+            Err(ReportError::InconsistentWidth {
+                line: 3,
+                column: 4,
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(
+            parse_report("0101\n  0121"),
+            Err(ReportError::InvalidLine { line: 2, column: 5 })
+        );
+        assert_eq!(oxygen_rating(&[]), None);
+    }
+
+    #[test]
+    fn query_prefixes_in_trie() {
+        let trie = BitTrie::from_report(&parse_report(SAMPLE).unwrap());
+
+        assert_eq!(trie.count(&[]), 12);
+        assert_eq!(trie.count(&[true, false]), 4);
+        assert_eq!(trie.count(&[false, false, false, false, false]), 0);
+        assert_eq!(trie.ones_and_zeros(&[]), (7, 5));
+        assert_eq!(trie.ones_and_zeros(&[true, false, true, true]), (1, 1));
+        assert_eq!(trie.ones_and_zeros(&[true, true, true, true, true]), (0, 0));
+    }
+
+    #[test]
+    fn ratings_keep_the_last_number_even_if_it_has_the_common_bit() {
+        let report = parse_report("110\n111\n000").unwrap();
+
+        // after the first bit only 110 and 111 are left for oxygen, while
+        // 000 is the single number left for CO2
+        assert_eq!(oxygen_rating(&report).unwrap().to_string(), "111");
+        assert_eq!(co2_rating(&report).unwrap().to_string(), "000");
+        let same = parse_report("101\n101").unwrap();
+        assert_eq!(co2_rating(&same).unwrap().to_string(), "101");
+    }
+
+    #[test]
+    fn support_any_width() {
+        let wide = format!("1{}", "0".repeat(199));
+        let bits = BitString::parse(&wide).unwrap();
+
+        assert_eq!(bits.width(), 200);
+        assert_eq!(bits.to_u128(), None);
+        assert_eq!(bits.to_biguint(), BigUint::from(1_u8) << 199_usize);
+        assert_eq!(bits.to_string(), wide);
+
+        let max = BitString::parse(&"1".repeat(128)).unwrap();
+        assert_eq!(max.to_u128(), Some(u128::MAX));
+    }
+}
@@ -0,0 +1,132 @@
+//! Random inputs for some 2021 puzzles, to fuzz their solvers against the
+//! `naive` implementations
+use crate::generators::Rng;
+
+/// Day 6: `size` lanternfishes with random timers
+pub fn lanternfish(rng: &mut Rng, size: usize) -> String {
+    (0..size)
+        .map(|_| rng.range(0, 9).to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Day 5: `size` vents in any direction within `-size..=size` on each axis
+pub fn vents(rng: &mut Rng, size: usize) -> String {
+    let bound = size as i64;
+    let coordinate = |rng: &mut Rng| rng.range(-bound, bound + 1);
+    (0..size)
+        .map(|_| {
+            let (x1, y1) = (coordinate(rng), coordinate(rng));
+            // mostly horizontal, vertical or diagonal vents, so that they overlap
+            let (x2, y2) = match rng.below(3) {
+                0 => (coordinate(rng), coordinate(rng)),
+                _ => {
+                    let (dx, dy) = [(1, 0), (0, 1), (1, 1), (1, -1)][rng.below(4) as usize];
+                    let len = rng.range(-bound, bound + 1);
+                    (x1 + dx * len, y1 + dy * len)
+                }
+            };
+            format!("{},{} -> {},{}", x1, y1, x2, y2)
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Day 14: a polymer template of length `size` and insertion rules for
+/// every pair of a few elements
+pub fn polymer(rng: &mut Rng, size: usize) -> String {
+    let elements: Vec<char> = "BCHNOPSV".chars().take(rng.range(2, 8) as usize).collect();
+    let pick = |rng: &mut Rng| elements[rng.below(elements.len() as u64) as usize];
+    let template: String = (0..size.max(2)).map(|_| pick(rng)).collect();
+    let mut lines = vec![template, String::new()];
+    for a in &elements {
+        for b in &elements {
+            lines.push(format!("{}{} -> {}", a, b, pick(rng)));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Day 22: `size` reboot steps over cuboids within `-size..=size` on each axis
+pub fn reactor(rng: &mut Rng, size: usize) -> String {
+    let bound = size as i64;
+    let range = |rng: &mut Rng| {
+        let a = rng.range(-bound, bound + 1);
+        let b = rng.range(-bound, bound + 1);
+        (a.min(b), a.max(b))
+    };
+    (0..size)
+        .map(|_| {
+            let state = if rng.below(3) == 0 { "off" } else { "on" };
+            let (x, y, z) = (range(rng), range(rng), range(rng));
+            format!(
+                "{} x={}..{},y={}..{},z={}..{}",
+                state, x.0, x.1, y.0, y.1, z.0, z.1
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Day 24: a MONAD-like program reading `size` digits, made of balanced
+/// "push" and "pop" blocks on a base 26 stack so that some inputs are valid.
+/// `size` is rounded up to an even number.
+pub fn monad(rng: &mut Rng, size: usize) -> String {
+    let pairs = size.div_ceil(2);
+    // a random well-parenthesized sequence of pushes (true) and pops (false)
+    let mut blocks = vec![];
+    let (mut opened, mut closed) = (0, 0);
+    while closed < pairs {
+        let can_push = opened < pairs;
+        let can_pop = opened > closed;
+        if can_push && (!can_pop || rng.below(2) == 0) {
+            blocks.push(true);
+            opened += 1;
+        } else {
+            blocks.push(false);
+            closed += 1;
+        }
+    }
+    let mut lines = vec![];
+    for push in blocks {
+        let (div, check) = if push {
+            (1, rng.range(10, 16))
+        } else {
+            (26, rng.range(-8, 1))
+        };
+        let offset = rng.range(1, 17);
+        lines.push("inp w".to_string());
+        lines.extend(
+            ["mul x 0", "add x z", "mod x 26"]
+                .iter()
+                .map(|s| s.to_string()),
+        );
+        lines.push(format!("div z {}", div));
+        lines.push(format!("add x {}", check));
+        lines.extend(
+            [
+                "eql x w", "eql x 0", "mul y 0", "add y 25", "mul y x", "add y 1", "mul z y",
+                "mul y 0", "add y w",
+            ]
+            .iter()
+            .map(|s| s.to_string()),
+        );
+        lines.push(format!("add y {}", offset));
+        lines.push("mul y x".to_string());
+        lines.push("add z y".to_string());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn monad_has_18_instructions_per_digit() {
+        let program = monad(&mut Rng::new(5), 6);
+
+        assert_eq!(program.lines().count(), 6 * 18);
+        assert_eq!(program.lines().filter(|l| *l == "div z 26").count(), 3);
+    }
+}
@@ -0,0 +1,273 @@
+//! Age-structured population model: fishes are counted by the number of days
+//! left before they spawn, instead of being simulated one by one
+use std::convert::TryFrom;
+use std::fmt;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Species {
+    pub name: String,
+    /// Days between two spawns of an adult
+    pub cycle: usize,
+    /// Extra days before a newborn spawns for the first time
+    pub delay: usize,
+    /// Newborns of each spawn
+    pub offspring: u64,
+}
+
+impl Species {
+    /// The fishes of the puzzle, spawning one fish every 7 days
+    pub fn lanternfish() -> Species {
+        Species {
+            name: "lanternfish".to_string(),
+            cycle: 7,
+            delay: 2,
+            offspring: 1,
+        }
+    }
+
+    /// Parse `name:cycle:delay:offspring`
+    pub fn parse(s: &str) -> Option<Species> {
+        let fields: Vec<&str> = s.split(':').collect();
+        match fields[..] {
+            [name, cycle, delay, offspring] if !name.is_empty() => {
+                let species = Species {
+                    name: name.to_string(),
+                    cycle: cycle.parse().ok()?,
+                    delay: delay.parse().ok()?,
+                    offspring: offspring.parse().ok()?,
+                };
+                Some(species).filter(|s| s.cycle > 0)
+            }
+            _ => None,
+        }
+    }
+
+    /// Number of age buckets, from 0 to the timer of a newborn
+    pub fn ages(&self) -> usize {
+        self.cycle + self.delay
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Population {
+    pub species: Species,
+    /// Number of fishes for each timer, from 0 to `ages() - 1`
+    pub counts: Vec<u64>,
+}
+
+impl Population {
+    pub fn new(species: Species) -> Population {
+        let counts = vec![0; species.ages()];
+        Population { species, counts }
+    }
+
+    /// A population with one fish for each timer, if they are all valid
+    pub fn from_timers(species: Species, timers: &[i64]) -> Option<Population> {
+        let mut population = Population::new(species);
+        for t in timers {
+            let age = usize::try_from(*t).ok()?;
+            *population.counts.get_mut(age)? += 1;
+        }
+        Some(population)
+    }
+
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// One day: the timers go down, fishes at 0 start a new cycle and spawn.
+    /// Returns `None`, leaving the counts unchanged, when the fishes would be
+    /// too many to count.
+    pub fn step(&mut self) -> Option<()> {
+        let spawning = self.counts[0];
+        let newborn = self.counts.len() - 1;
+        let mut counts = self.counts.clone();
+        counts.rotate_left(1);
+        counts[newborn] = spawning.checked_mul(self.species.offspring)?;
+        let restart = &mut counts[self.species.cycle - 1];
+        *restart = restart.checked_add(spawning)?;
+        counts
+            .iter()
+            .try_fold(0_u64, |sum, n| sum.checked_add(*n))?;
+        self.counts = counts;
+        Some(())
+    }
+}
+
+/// The fishes of all species no longer fit in a `u64` after some day
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow {
+    pub day: u32,
+}
+
+impl Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "too many fishes to count on day {}", self.day)
+    }
+}
+
+/// Several species living side by side
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Simulation {
+    pub populations: Vec<Population>,
+    /// Days elapsed since the start
+    pub day: u32,
+}
+
+impl Simulation {
+    pub fn new(populations: Vec<Population>) -> Simulation {
+        Simulation {
+            populations,
+            day: 0,
+        }
+    }
+
+    /// One day for every species, or an error leaving the simulation
+    /// unchanged when the fishes would be too many to count
+    pub fn step(&mut self) -> Result<(), Overflow> {
+        let overflow = Overflow { day: self.day + 1 };
+        let mut populations = self.populations.clone();
+        for p in &mut populations {
+            p.step().ok_or(overflow)?;
+        }
+        populations
+            .iter()
+            .try_fold(0_u64, |sum, p| sum.checked_add(p.total()))
+            .ok_or(overflow)?;
+        self.populations = populations;
+        self.day += 1;
+        Ok(())
+    }
+
+    /// Run for some days, calling `observe` before the first day and after
+    /// each of them, stopping on the first day the fishes overflow
+    pub fn run<F: FnMut(&Simulation)>(
+        &mut self,
+        days: u32,
+        mut observe: F,
+    ) -> Result<(), Overflow> {
+        observe(self);
+        for _ in 0..days {
+            self.step()?;
+            observe(self);
+        }
+        Ok(())
+    }
+
+    pub fn total(&self) -> u64 {
+        self.populations.iter().map(Population::total).sum()
+    }
+
+    /// Run for some days, writing the counts of every age bucket each day as
+    /// CSV, with a header line
+    pub fn write_csv<W: Write>(&mut self, days: u32, mut out: W) -> io::Result<()> {
+        let mut header = vec!["day".to_string()];
+        for p in &self.populations {
+            header.extend((0..p.counts.len()).map(|age| format!("{}_{}", p.species.name, age)));
+        }
+        writeln!(out, "{}", header.join(","))?;
+        let mut res = Ok(());
+        let run = self.run(days, |sim| {
+            if res.is_ok() {
+                let counts = sim.populations.iter().flat_map(|p| &p.counts);
+                let row: Vec<String> = std::iter::once(sim.day as u64)
+                    .chain(counts.copied())
+                    .map(|n| n.to_string())
+                    .collect();
+                res = writeln!(out, "{}", row.join(","));
+            }
+        });
+        res?;
+        run.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Population {
+        Population::from_timers(Species::lanternfish(), &[3, 4, 3, 1, 2]).unwrap()
+    }
+
+    #[test]
+    fn lanternfish_sample() {
+        let mut sim = Simulation::new(vec![sample()]);
+        sim.run(18, |_| {}).unwrap();
+        assert_eq!(sim.total(), 26);
+        sim.run(80 - 18, |_| {}).unwrap();
+        assert_eq!(sim.total(), 5934);
+        sim.run(256 - 80, |_| {}).unwrap();
+        assert_eq!(sim.total(), 26984457539);
+        assert_eq!(sim.day, 256);
+    }
+
+    #[test]
+    fn reject_invalid_timers() {
+        assert_eq!(Population::from_timers(Species::lanternfish(), &[9]), None);
+        assert_eq!(Population::from_timers(Species::lanternfish(), &[-1]), None);
+    }
+
+    #[test]
+    fn species_with_more_offspring() {
+        let twins = Species::parse("twins:2:0:2").unwrap();
+        let mut population = Population::from_timers(twins, &[0]).unwrap();
+        let mut totals = vec![];
+        for _ in 0..4 {
+            population.step().unwrap();
+            totals.push(population.total());
+        }
+        assert_eq!(totals, [3, 3, 9, 9]);
+        assert_eq!(Species::parse("none:0:1:1"), None);
+        assert_eq!(Species::parse("short:7:2"), None);
+    }
+
+    #[test]
+    fn observe_every_day() {
+        let mut sim = Simulation::new(vec![sample(), sample()]);
+        let mut totals = vec![];
+        sim.run(3, |s| totals.push(s.total())).unwrap();
+        assert_eq!(totals, [10, 10, 12, 14]);
+    }
+
+    #[test]
+    fn stop_when_fishes_overflow() {
+        let fast = Species::parse("fast:2:0:3").unwrap();
+        let population = Population::from_timers(fast, &[1, 0, 1]).unwrap();
+        let mut sim = Simulation::new(vec![population]);
+        let mut days = 0;
+        let res = sim.run(100, |_| days += 1);
+        // the fishes quadruple every two days
+        assert_eq!(res, Err(Overflow { day: 63 }));
+        assert_eq!(sim.day, 62);
+        assert_eq!(days, 63);
+        assert!(sim.total() > u64::MAX / 4);
+        assert_eq!(sim.step(), Err(Overflow { day: 63 }));
+        assert_eq!(sim.day, 62);
+        assert!(sim.write_csv(1, vec![]).is_err());
+    }
+
+    #[test]
+    fn csv_time_series() {
+        let other = Species::parse("other:1:1:1").unwrap();
+        let mut sim = Simulation::new(vec![
+            sample(),
+            Population::from_timers(other, &[1]).unwrap(),
+        ]);
+        let mut out = vec![];
+        sim.write_csv(2, &mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "day,lanternfish_0,lanternfish_1,lanternfish_2,lanternfish_3,lanternfish_4,\
+             lanternfish_5,lanternfish_6,lanternfish_7,lanternfish_8,other_0,other_1"
+        );
+        assert_eq!(lines[1], "0,0,1,1,2,1,0,0,0,0,0,1");
+        assert_eq!(lines[2], "1,1,1,2,1,0,0,0,0,0,1,0");
+        assert_eq!(lines[3], "2,1,2,1,0,0,0,1,0,1,1,1");
+        assert_eq!(lines.len(), 4);
+    }
+}
@@ -0,0 +1,255 @@
+//! Solutions and puzzle-specific models for Advent of Code 2021
+use crate::derivative::{count_increasing, window_sums};
+use crate::parser::move_order;
+use crate::runner::{numbered_lines, parse_lines, parse_whole, ParseError, Solution};
+use basins::Connectivity;
+use crabs::{Linear, Triangular};
+use lanternfish::{Overflow, Population, Simulation, Species};
+use submarine::{Aim, Plain, Submarine};
+
+pub mod basins;
+pub mod bingo;
+pub mod bingo_odds;
+pub mod bingo_search;
+pub mod crabs;
+pub mod diagnostic;
+pub mod generators;
+pub mod lanternfish;
+pub mod naive;
+pub mod segments;
+pub mod submarine;
+pub mod vent_crossings;
+pub mod vent_index;
+pub mod vents;
+
+pub const YEAR: u16 = 2021;
+
+/// The 2021 solutions which can run from the library
+pub fn solutions() -> Vec<Solution> {
+    vec![
+        Solution {
+            year: YEAR,
+            day: 1,
+            solve: solve_day1,
+        },
+        Solution {
+            year: YEAR,
+            day: 2,
+            solve: solve_day2,
+        },
+        Solution {
+            year: YEAR,
+            day: 3,
+            solve: solve_day3,
+        },
+        Solution {
+            year: YEAR,
+            day: 4,
+            solve: solve_day4,
+        },
+        Solution {
+            year: YEAR,
+            day: 5,
+            solve: solve_day5,
+        },
+        Solution {
+            year: YEAR,
+            day: 6,
+            solve: solve_day6,
+        },
+        Solution {
+            year: YEAR,
+            day: 7,
+            solve: solve_day7,
+        },
+        Solution {
+            year: YEAR,
+            day: 8,
+            solve: solve_day8,
+        },
+        Solution {
+            year: YEAR,
+            day: 9,
+            solve: solve_day9,
+        },
+    ]
+}
+
+fn solve_day1(input: &str) -> Result<Vec<String>, ParseError> {
+    let depths = parse_lines(input, |l| {
+        l.trim()
+            .parse::<i32>()
+            .map_err(|_| l.len() - l.trim_start().len())
+    })?;
+    Ok(vec![
+        count_increasing(&depths).to_string(),
+        count_increasing(window_sums(depths.iter().copied(), 3)).to_string(),
+    ])
+}
+
+fn solve_day2(input: &str) -> Result<Vec<String>, ParseError> {
+    let moves = parse_lines(input, |l| parse_whole(l, move_order))?;
+    let plain = Submarine::new(Plain).run_all(moves.clone()).unwrap();
+    let aim = Submarine::new(Aim).run_all(moves).unwrap();
+    Ok(vec![
+        (plain.position * plain.depth).to_string(),
+        (aim.position * aim.depth).to_string(),
+    ])
+}
+
+fn solve_day3(input: &str) -> Result<Vec<String>, ParseError> {
+    let report = diagnostic::parse_report(input).map_err(|e| {
+        let (line, column) = match e {
+            diagnostic::ReportError::InvalidLine { line, column } => (line, column),
+            diagnostic::ReportError::InconsistentWidth { line, column, .. } => (line, column),
+        };
+        ParseError {
+            line,
+            column,
+            message: e.to_string(),
+        }
+    })?;
+    let power = diagnostic::gamma(&report).to_biguint() * diagnostic::epsilon(&report).to_biguint();
+    let life_support = diagnostic::oxygen_rating(&report)
+        .zip(diagnostic::co2_rating(&report))
+        .map(|(oxygen, co2)| oxygen.to_biguint() * co2.to_biguint());
+    Ok(vec![power.to_string()]
+        .into_iter()
+        .chain(life_support.map(|v| v.to_string()))
+        .collect())
+}
+
+fn solve_day4(input: &str) -> Result<Vec<String>, ParseError> {
+    let lines: Vec<&str> = input.split('\n').collect();
+    let game = bingo::try_parse_bingo(&lines).map_err(|e| {
+        let (line, column) = match e {
+            bingo::BingoError::InvalidDraw { column } => (1, column),
+            bingo::BingoError::InvalidRow { line, column } => (line, column),
+            bingo::BingoError::RaggedBoard { line, column, .. } => (line, column),
+            bingo::BingoError::DuplicateNumber { line, column, .. } => (line, column),
+            // the boards are missing at the end of the input
+            bingo::BingoError::NoBoard => (lines.len(), lines.last().map_or(0, |l| l.len()) + 1),
+        };
+        ParseError {
+            line,
+            column,
+            message: e.to_string(),
+        }
+    })?;
+    let res = bingo::play(&game);
+    Ok(res
+        .first()
+        .into_iter()
+        .chain(res.last())
+        .map(|win| win.score.to_string())
+        .collect())
+}
+
+fn solve_day5(input: &str) -> Result<Vec<String>, ParseError> {
+    let all = parse_lines(input, |l| parse_whole(l, vents::vent))?;
+    let ortho: Vec<vents::Vent> = all.iter().cloned().filter(vents::is_ortho).collect();
+    Ok(vec![
+        vents::overlaps(&ortho).to_string(),
+        vents::overlaps(&all).to_string(),
+    ])
+}
+
+fn solve_day6(input: &str) -> Result<Vec<String>, ParseError> {
+    let species = Species::lanternfish();
+    let ages = species.ages() as i64;
+    let timers = parse_numbers(input, |t| (0..ages).contains(&t))?;
+    let population = Population::from_timers(species, &timers).unwrap();
+    let mut sim = Simulation::new(vec![population]);
+    // only billions of fishes overflow, so the error is at the last of them
+    let overflow = |e: Overflow| ParseError {
+        line: numbered_lines(input).last().map_or(1, |(line, _)| line),
+        column: 1,
+        message: e.to_string(),
+    };
+    sim.run(80, |_| {}).map_err(overflow)?;
+    let part1 = sim.total();
+    sim.run(256 - 80, |_| {}).map_err(overflow)?;
+    Ok(vec![part1.to_string(), sim.total().to_string()])
+}
+
+/// Comma-separated numbers, possibly over several lines, all of them valid
+fn parse_numbers(input: &str, valid: impl Fn(i64) -> bool) -> Result<Vec<i64>, ParseError> {
+    let lines = parse_lines(input, |line| {
+        let mut res = vec![];
+        let mut offset = 0;
+        for token in line.split(',') {
+            let start = offset + token.len() - token.trim_start().len();
+            match token.trim().parse() {
+                Ok(n) if valid(n) => res.push(n),
+                _ => return Err(start),
+            }
+            offset += token.len() + 1;
+        }
+        Ok(res)
+    })?;
+    Ok(lines.concat())
+}
+
+fn solve_day7(input: &str) -> Result<Vec<String>, ParseError> {
+    let positions = parse_numbers(input, |_| true)?;
+    Ok([
+        crabs::solve(&positions, &Linear),
+        crabs::solve(&positions, &Triangular),
+    ]
+    .iter()
+    .flatten()
+    .map(|best| best.fuel.to_string())
+    .collect())
+}
+
+fn solve_day8(input: &str) -> Result<Vec<String>, ParseError> {
+    let alphabet = segments::Alphabet::seven_segment();
+    let lines = parse_lines(input, |line| alphabet.parse_line(line))?;
+    let easy = lines
+        .iter()
+        .flat_map(|l| &l.outputs)
+        .filter(|p| [2, 3, 4, 7].contains(&p.count_ones()))
+        .count();
+    let mut sum: u64 = 0;
+    for ((number, text), line) in numbered_lines(input).zip(&lines) {
+        let decoded = alphabet.decode(line).map_err(|e| ParseError {
+            line: number,
+            column: 1,
+            message: e.to_string(),
+        })?;
+        // the outputs start after the bar
+        let after_bar = text.find('|').map_or(0, |bar| bar + 1);
+        let outputs = &text[after_bar..];
+        let not_a_number = |message: String| ParseError {
+            line: number,
+            column: after_bar + outputs.len() - outputs.trim_start().len() + 1,
+            message,
+        };
+        let value = decoded
+            .value(10)
+            .ok_or_else(|| not_a_number(format!("outputs {} are not a number", decoded.symbols)))?;
+        sum = sum
+            .checked_add(value)
+            .ok_or_else(|| not_a_number("the sum of the outputs overflows".to_string()))?;
+    }
+    Ok(vec![easy.to_string(), sum.to_string()])
+}
+
+fn solve_day9(input: &str) -> Result<Vec<String>, ParseError> {
+    let heights = parse_lines(input, |line| {
+        let blanks = line.len() - line.trim_start().len();
+        line.trim()
+            .char_indices()
+            .map(|(i, c)| c.to_digit(10).map(|d| d as u8).ok_or(blanks + i))
+            .collect::<Result<Vec<u8>, usize>>()
+    })?;
+    let risk: u64 = basins::low_points(&heights, Connectivity::Four)
+        .iter()
+        .map(|(x, y)| basins::risk(heights[*y][*x]))
+        .sum();
+    let res = basins::label(&heights, |h| h == 9, Connectivity::Four);
+    let mut sizes: Vec<u64> = res.basins.iter().map(|b| b.size as u64).collect();
+    sizes.sort_unstable_by(|a, b| b.cmp(a));
+    let largest: u64 = sizes.iter().take(3).product();
+    Ok(vec![risk.to_string(), largest.to_string()])
+}
@@ -0,0 +1,170 @@
+//! Naive, obviously correct but slow, implementations of some puzzles used as
+//! oracles to check the actual solvers on inputs from the `generators` module
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+/// Day 6: simulate each lanternfish individually and count them after `days`
+pub fn lanternfish(input: &str, days: u32) -> u64 {
+    let mut fishes: Vec<u8> = input
+        .trim()
+        .split(',')
+        .map(|s| s.parse::<u8>().unwrap())
+        .collect();
+    for _ in 0..days {
+        let mut born = 0;
+        for f in fishes.iter_mut() {
+            if *f == 0 {
+                *f = 6;
+                born += 1;
+            } else {
+                *f -= 1;
+            }
+        }
+        fishes.extend(vec![8; born]);
+    }
+    fishes.len() as u64
+}
+
+/// Day 14: grow the literal polymer string for `steps` and return the
+/// difference between the most and least common elements
+pub fn polymer(input: &str, steps: u8) -> u64 {
+    let lines: Vec<&str> = input.split('\n').collect();
+    let rules: HashMap<(char, char), char> = lines[2..]
+        .iter()
+        .filter_map(|l| {
+            let cs: Vec<char> = l.chars().collect();
+            if cs.len() == 7 {
+                Some(((cs[0], cs[1]), cs[6]))
+            } else {
+                None
+            }
+        })
+        .collect();
+    let mut polymer: Vec<char> = lines[0].chars().collect();
+    for _ in 0..steps {
+        let mut next = vec![polymer[0]];
+        for w in polymer.windows(2) {
+            if let Some(c) = rules.get(&(w[0], w[1])) {
+                next.push(*c);
+            }
+            next.push(w[1]);
+        }
+        polymer = next;
+    }
+    let mut counts: HashMap<char, u64> = HashMap::new();
+    for c in polymer {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+    counts.values().max().unwrap() - counts.values().min().unwrap()
+}
+
+/// Day 22: apply every reboot step to an explicit set of lit cubes
+pub fn reactor(input: &str) -> i64 {
+    let mut on = HashSet::new();
+    for line in input.split('\n').filter(|l| !l.is_empty()) {
+        let parts: Vec<&str> = line.split(' ').collect();
+        let ranges: Vec<(i64, i64)> = parts[1]
+            .split(',')
+            .map(|r| {
+                let bounds: Vec<i64> = r[2..].split("..").map(|v| v.parse().unwrap()).collect();
+                (bounds[0], bounds[1])
+            })
+            .collect();
+        for x in ranges[0].0..=ranges[0].1 {
+            for y in ranges[1].0..=ranges[1].1 {
+                for z in ranges[2].0..=ranges[2].1 {
+                    if parts[0] == "on" {
+                        on.insert((x, y, z));
+                    } else {
+                        on.remove(&(x, y, z));
+                    }
+                }
+            }
+        }
+    }
+    on.len() as i64
+}
+
+/// Run an ALU program on some digits and return the final value of `z`
+pub fn alu(program: &str, digits: &[i64]) -> i64 {
+    let mut regs = [0_i64; 4];
+    let mut inputs = digits.iter();
+    let index = |r: &str| (r.as_bytes()[0] - b'w') as usize;
+    for line in program.split('\n').filter(|l| !l.is_empty()) {
+        let parts: Vec<&str> = line.split(' ').collect();
+        let a = index(parts[1]);
+        if parts[0] == "inp" {
+            regs[a] = *inputs.next().unwrap();
+            continue;
+        }
+        let b = parts[2]
+            .parse::<i64>()
+            .unwrap_or_else(|_| regs[index(parts[2])]);
+        regs[a] = match parts[0] {
+            "add" => regs[a] + b,
+            "mul" => regs[a] * b,
+            "div" => regs[a] / b,
+            "mod" => regs[a] % b,
+            _ => (regs[a] == b) as i64,
+        };
+    }
+    regs[3]
+}
+
+/// Day 24: try every model number accepted by the program, returning the
+/// smallest one if any
+pub fn monad(program: &str) -> Option<u64> {
+    let size = program.split('\n').filter(|l| l.starts_with("inp")).count();
+    let mut digits = vec![1_i64; size];
+    loop {
+        if alu(program, &digits) == 0 {
+            return Some(digits.iter().fold(0, |n, d| n * 10 + *d as u64));
+        }
+        // next number in increasing order, using only digits 1 to 9
+        let mut i = size;
+        loop {
+            if i == 0 {
+                return None;
+            }
+            i -= 1;
+            if digits[i] < 9 {
+                digits[i] += 1;
+                break;
+            }
+            digits[i] = 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lanternfish_sample() {
+        assert_eq!(lanternfish("3,4,3,1,2", 18), 26);
+        assert_eq!(lanternfish("3,4,3,1,2", 80), 5934);
+    }
+
+    #[test]
+    fn polymer_sample() {
+        let input = "NNCB\n\nCH -> B\nHH -> N\nCB -> H\nNH -> C\nHB -> C\nHC -> B\nHN -> C\nNN -> C\nBH -> H\nNC -> B\nNB -> B\nBN -> B\nBB -> N\nBC -> B\nCC -> N\nCN -> C";
+
+        assert_eq!(polymer(input, 10), 1588);
+    }
+
+    #[test]
+    fn reactor_sample() {
+        let input = "on x=10..12,y=10..12,z=10..12\non x=11..13,y=11..13,z=11..13\noff x=9..11,y=9..11,z=9..11\non x=10..10,y=10..10,z=10..10";
+
+        assert_eq!(reactor(input), 39);
+    }
+
+    #[test]
+    fn monad_finds_smallest_accepted_number() {
+        // z = w0 + 1 is pushed then popped when w1 == w0 + 1 - 3
+        let program = "inp w\nmul x 0\nadd x z\nmod x 26\ndiv z 1\nadd x 11\neql x w\neql x 0\nmul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y 1\nmul y x\nadd z y\ninp w\nmul x 0\nadd x z\nmod x 26\ndiv z 26\nadd x -3\neql x w\neql x 0\nmul y 0\nadd y 25\nmul y x\nadd y 1\nmul z y\nmul y 0\nadd y w\nadd y 4\nmul y x\nadd z y";
+
+        assert_eq!(monad(program), Some(31));
+    }
+}
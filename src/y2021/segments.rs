@@ -0,0 +1,765 @@
+//! Unscrambling segment displays whose wires were mixed up: each wire lights
+//! an unknown segment, found by constraint propagation from the patterns of
+//! lit wires. The symbols a display can show come from a glyph table.
+use nom::Offset;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::fmt::Display;
+
+/// Bit set of wires or segments, `a` being the lowest bit
+pub type Pattern = u32;
+
+/// The digits of a seven-segment display, with the segments in the order of
+/// the puzzle: top, top left, top right, middle, bottom left, bottom right,
+/// bottom
+pub const SEVEN_SEGMENT: &str = "\
+0 abcefg
+1 cf
+2 acdeg
+3 acdfg
+4 bcdf
+5 abdfg
+6 abdefg
+7 acf
+8 abcdefg
+9 abcdfg";
+
+/// The digits of a seven-segment display, then `A` to `F`, with `B` and `D`
+/// shown in lowercase
+pub const HEXADECIMAL: &str = "\
+0 abcefg
+1 cf
+2 acdeg
+3 acdfg
+4 bcdf
+5 abdfg
+6 abdefg
+7 acf
+8 abcdefg
+9 abcdfg
+A abcdef
+B bdefg
+C abeg
+D cdefg
+E abdeg
+F abde";
+
+/// Digits and capital letters on a fourteen-segment display: top, top
+/// right, bottom right, bottom, bottom left, top left, middle left, middle
+/// right, then the inner top left diagonal, top vertical, top right
+/// diagonal, bottom left diagonal, bottom vertical and bottom right diagonal
+pub const FOURTEEN_SEGMENT: &str = "\
+0 abcdefkl
+1 bck
+2 abdegh
+3 abcdh
+4 bcfgh
+5 acdfgh
+6 acdefgh
+7 abc
+8 abcdefgh
+9 abcdfgh
+A abcefgh
+B abcdhjm
+C adef
+D abcdjm
+E adefg
+F aefg
+G acdefh
+H bcefgh
+I adjm
+J bcde
+K efgkn
+L def
+M bcefik
+N bcefin
+O abcdef
+P abefgh
+Q abcdefn
+R abefghn
+S acdhi
+T ajm
+U bcdef
+V efkl
+W bcefln
+X ikln
+Y ikm
+Z adkl";
+
+/// The glyphs of the fourteen-segment display with the top and bottom
+/// segments split in two: top left, top right, then clockwise the outer
+/// segments down to the top left one, the middle left and right, then the
+/// inner segments as for fourteen segments. Brackets light only one half of
+/// the top and bottom segments, which tells the halves apart.
+pub const SIXTEEN_SEGMENT: &str = "\
+0 abcdefghmn
+1 cdm
+2 abcefgij
+3 abcdefj
+4 cdhij
+5 abdefhij
+6 abdefghij
+7 abcd
+8 abcdefghij
+9 abcdefhij
+A abcdghij
+B abcdefjlo
+C abefgh
+D abcdeflo
+E abefghi
+F abghi
+G abdefghj
+H cdghij
+I abeflo
+J cdefg
+K ghimp
+L efgh
+M cdghkm
+N cdghkp
+O abcdefgh
+P abcghij
+Q abcdefghp
+R abcghijp
+S abdefjk
+T ablo
+U cdefgh
+V ghmn
+W cdghnp
+X kmnp
+Y kmo
+Z abefmn
+[ afgh
+] bcde";
+
+/// Parse letters from `a` as a bit set, each at most once, with at most
+/// `segments` letters
+fn parse_bits(s: &str, segments: usize) -> Option<Pattern> {
+    let mut res = 0;
+    for c in s.chars() {
+        let bit = (c as u32).checked_sub('a' as u32)?;
+        if bit as usize >= segments || res & 1 << bit != 0 {
+            return None;
+        }
+        res |= 1 << bit;
+    }
+    Some(res).filter(|p| *p != 0)
+}
+
+/// A glyph table which cannot be loaded, lines starting at 1
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GlyphError {
+    InvalidLine {
+        line: usize,
+    },
+    DuplicateSymbol {
+        line: usize,
+        symbol: char,
+    },
+    SameGlyph {
+        line: usize,
+        symbol: char,
+        other: char,
+    },
+    Empty,
+}
+
+impl Display for GlyphError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlyphError::InvalidLine { line } => {
+                write!(f, "line {} is not a symbol and its segments", line)
+            }
+            GlyphError::DuplicateSymbol { line, symbol } => {
+                write!(f, "line {} defines '{}' again", line, symbol)
+            }
+            GlyphError::SameGlyph {
+                line,
+                symbol,
+                other,
+            } => write!(f, "line {}: '{}' looks like '{}'", line, symbol, other),
+            GlyphError::Empty => write!(f, "no glyph"),
+        }
+    }
+}
+
+/// The symbols of a display, with the segments each of them lights
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alphabet {
+    segments: usize,
+    glyphs: Vec<(char, Pattern)>,
+}
+
+impl Alphabet {
+    /// Load a glyph table: one symbol per line followed by its lit segments,
+    /// as letters from `a`. Empty lines and lines starting with `#` are
+    /// skipped. The display has as many segments as the last letter used.
+    pub fn parse(table: &str) -> Result<Alphabet, GlyphError> {
+        let mut glyphs: Vec<(char, Pattern)> = vec![];
+        for (i, line) in table.split('\n').enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = GlyphError::InvalidLine { line: i + 1 };
+            let mut chars = line.chars();
+            let symbol = chars.next().ok_or_else(|| invalid.clone())?;
+            let segments = chars.as_str();
+            if !segments.starts_with(char::is_whitespace) {
+                return Err(invalid);
+            }
+            let glyph = parse_bits(segments.trim(), 26).ok_or(invalid)?;
+            for (other, g) in &glyphs {
+                if *other == symbol {
+                    return Err(GlyphError::DuplicateSymbol {
+                        line: i + 1,
+                        symbol,
+                    });
+                }
+                if *g == glyph {
+                    return Err(GlyphError::SameGlyph {
+                        line: i + 1,
+                        symbol,
+                        other: *other,
+                    });
+                }
+            }
+            glyphs.push((symbol, glyph));
+        }
+        let all = glyphs.iter().fold(0, |acc, (_, g)| acc | g);
+        if all == 0 {
+            return Err(GlyphError::Empty);
+        }
+        Ok(Alphabet {
+            segments: (Pattern::BITS - all.leading_zeros()) as usize,
+            glyphs,
+        })
+    }
+
+    fn builtin(table: &str) -> Alphabet {
+        Alphabet::parse(table).expect("invalid builtin glyph table")
+    }
+
+    pub fn seven_segment() -> Alphabet {
+        Alphabet::builtin(SEVEN_SEGMENT)
+    }
+
+    pub fn hexadecimal() -> Alphabet {
+        Alphabet::builtin(HEXADECIMAL)
+    }
+
+    pub fn fourteen_segment() -> Alphabet {
+        Alphabet::builtin(FOURTEEN_SEGMENT)
+    }
+
+    pub fn sixteen_segment() -> Alphabet {
+        Alphabet::builtin(SIXTEEN_SEGMENT)
+    }
+
+    /// Number of segments, and of wires
+    pub fn segments(&self) -> usize {
+        self.segments
+    }
+
+    fn all(&self) -> Pattern {
+        (1 << self.segments) - 1
+    }
+
+    /// The segments lit for a symbol
+    pub fn glyph(&self, symbol: char) -> Option<Pattern> {
+        self.glyphs.iter().find(|(s, _)| *s == symbol).map(|g| g.1)
+    }
+
+    /// The symbol shown by some lit segments
+    pub fn symbol(&self, lit: Pattern) -> Option<char> {
+        self.glyphs.iter().find(|(_, g)| *g == lit).map(|g| g.0)
+    }
+
+    /// Parse the wires of a pattern, each at most once
+    pub fn parse_pattern(&self, s: &str) -> Option<Pattern> {
+        parse_bits(s, self.segments)
+    }
+
+    /// Parse `patterns | outputs`, each side a list of patterns, possibly
+    /// empty. Fails with the offset of the first invalid pattern, or the
+    /// length of the line without `|`.
+    pub fn parse_line(&self, s: &str) -> Result<Line, usize> {
+        let bar = s.find('|').ok_or(s.len())?;
+        let parse = |side: &str| -> Result<Vec<Pattern>, usize> {
+            side.split_whitespace()
+                .map(|p| self.parse_pattern(p).ok_or_else(|| s.offset(p)))
+                .collect()
+        };
+        Ok(Line {
+            patterns: parse(&s[..bar])?,
+            outputs: parse(&s[bar + 1..])?,
+        })
+    }
+
+    /// Up to `limit` wirings under which all the patterns show symbols
+    pub fn wirings(&self, patterns: &[Pattern], limit: usize) -> Vec<Wiring> {
+        let mut found = vec![];
+        if limit > 0 {
+            Domains::new(self).search(self, patterns, &mut |w| {
+                found.push(w.clone());
+                found.len() < limit
+            });
+        }
+        found
+    }
+
+    /// Read the outputs of a line, using its patterns and outputs as clues.
+    /// This works with only some of the symbols seen, as long as they leave
+    /// a single reading of the outputs.
+    pub fn decode(&self, line: &Line) -> Result<Decoded, LineError> {
+        let clues: Vec<Pattern> = line.patterns.iter().chain(&line.outputs).copied().collect();
+        let mut readings = BTreeSet::new();
+        Domains::new(self).read(
+            self,
+            &clues,
+            &line.outputs,
+            &mut String::new(),
+            &mut readings,
+        );
+        match readings.len() {
+            0 => Err(LineError::Inconsistent),
+            1 => {
+                let wirings = self.wirings(&clues, 2);
+                Ok(Decoded {
+                    wiring: Some(wirings[0].clone()).filter(|_| wirings.len() == 1),
+                    symbols: readings.into_iter().next().unwrap(),
+                })
+            }
+            _ => Err(LineError::Ambiguous {
+                readings: readings.into_iter().collect(),
+            }),
+        }
+    }
+}
+
+/// The patterns seen on a display, then the ones of the symbols to read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    pub patterns: Vec<Pattern>,
+    pub outputs: Vec<Pattern>,
+}
+
+/// The segment lit by each wire
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wiring {
+    segments: Vec<usize>,
+}
+
+impl Wiring {
+    /// The segment lit by a wire, 0 for `a`
+    pub fn segment(&self, wire: usize) -> usize {
+        self.segments[wire]
+    }
+
+    /// Segments lit by a pattern of wires
+    pub fn apply(&self, pattern: Pattern) -> Pattern {
+        (0..self.segments.len())
+            .filter(|w| pattern & 1 << w != 0)
+            .fold(0, |acc, w| acc | 1 << self.segments[w])
+    }
+}
+
+impl Display for Wiring {
+    /// The segment of each wire, as the letter of the wire lighting it
+    /// on a display wired as expected
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for s in self.segments.iter() {
+            write!(f, "{}", (b'a' + *s as u8) as char)?;
+        }
+        Ok(())
+    }
+}
+
+/// Possible segments of each wire, narrowed down by the patterns
+#[derive(Debug, Clone)]
+struct Domains(Vec<Pattern>);
+
+impl Domains {
+    fn new(alphabet: &Alphabet) -> Domains {
+        Domains(vec![alphabet.all(); alphabet.segments])
+    }
+
+    /// Narrow the domains until every pattern is consistent with them,
+    /// returning false when some wire has no segment left
+    fn propagate(&mut self, alphabet: &Alphabet, patterns: &[Pattern]) -> bool {
+        loop {
+            let before = self.0.clone();
+            for p in patterns {
+                // the glyphs the pattern may still be
+                let mut lit = 0;
+                let mut unlit = 0;
+                for (_, g) in alphabet.glyphs.iter() {
+                    if g.count_ones() == p.count_ones() && self.allows(*p, *g) {
+                        lit |= g;
+                        unlit |= alphabet.all() & !g;
+                    }
+                }
+                for (w, domain) in self.0.iter_mut().enumerate() {
+                    *domain &= if p & 1 << w != 0 { lit } else { unlit };
+                }
+            }
+            // a segment known to be lit by a wire is not lit by the others
+            for w in 0..self.0.len() {
+                if self.0[w].count_ones() == 1 {
+                    let segment = self.0[w];
+                    for (other, domain) in self.0.iter_mut().enumerate() {
+                        if other != w {
+                            *domain &= !segment;
+                        }
+                    }
+                }
+            }
+            if self.0.contains(&0) {
+                return false;
+            }
+            if self.0 == before {
+                return true;
+            }
+        }
+    }
+
+    /// Whether the wires of a pattern may light the segments of a glyph
+    fn allows(&self, pattern: Pattern, glyph: Pattern) -> bool {
+        self.0.iter().enumerate().all(|(w, domain)| {
+            let allowed = if pattern & 1 << w != 0 { glyph } else { !glyph };
+            domain & allowed != 0
+        })
+    }
+
+    fn wiring(&self) -> Wiring {
+        Wiring {
+            segments: self.0.iter().map(|d| d.trailing_zeros() as usize).collect(),
+        }
+    }
+
+    /// Call `visit` with every wiring under which all the patterns show
+    /// symbols, stopping as soon as it returns false and returning false too
+    fn search(
+        mut self,
+        alphabet: &Alphabet,
+        patterns: &[Pattern],
+        visit: &mut dyn FnMut(&Wiring) -> bool,
+    ) -> bool {
+        if !self.propagate(alphabet, patterns) {
+            return true;
+        }
+        // branch on the wire with the fewest segments left
+        let branch = (0..self.0.len())
+            .filter(|w| self.0[*w].count_ones() > 1)
+            .min_by_key(|w| self.0[*w].count_ones());
+        match branch {
+            Some(w) => {
+                for s in 0..alphabet.segments {
+                    if self.0[w] & 1 << s != 0 {
+                        let mut next = self.clone();
+                        next.0[w] = 1 << s;
+                        if !next.search(alphabet, patterns, visit) {
+                            return false;
+                        }
+                    }
+                }
+                true
+            }
+            None => {
+                let wiring = self.wiring();
+                let shows = |p: &Pattern| alphabet.symbol(wiring.apply(*p)).is_some();
+                !patterns.iter().all(shows) || visit(&wiring)
+            }
+        }
+    }
+
+    /// Add to `readings` the symbols the `outputs` may show, until there are
+    /// two of them. Branching on the symbol of each output rather than on
+    /// wires keeps the many wirings reading the same symbols from being
+    /// enumerated.
+    fn read(
+        mut self,
+        alphabet: &Alphabet,
+        patterns: &[Pattern],
+        outputs: &[Pattern],
+        reading: &mut String,
+        readings: &mut BTreeSet<String>,
+    ) {
+        if readings.len() > 1 || !self.propagate(alphabet, patterns) {
+            return;
+        }
+        match outputs.split_first() {
+            None => {
+                let mut complete = false;
+                self.search(alphabet, patterns, &mut |_| {
+                    complete = true;
+                    false
+                });
+                if complete {
+                    readings.insert(reading.clone());
+                }
+            }
+            Some((p, rest)) => {
+                for (symbol, g) in alphabet.glyphs.iter() {
+                    if g.count_ones() == p.count_ones() && self.allows(*p, *g) {
+                        let mut next = self.clone();
+                        for (w, domain) in next.0.iter_mut().enumerate() {
+                            *domain &= if p & 1 << w != 0 { *g } else { !g };
+                        }
+                        reading.push(*symbol);
+                        next.read(alphabet, patterns, rest, reading, readings);
+                        reading.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Why the outputs of a line cannot be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineError {
+    /// No wiring shows symbols for all the patterns
+    Inconsistent,
+    /// Wirings fit reading the outputs differently, some of them given
+    Ambiguous { readings: Vec<String> },
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LineError::Inconsistent => write!(f, "no wiring shows symbols for all patterns"),
+            LineError::Ambiguous { readings } => {
+                write!(f, "the output reads as {}", readings.join(" or "))
+            }
+        }
+    }
+}
+
+/// A line whose outputs could be read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Decoded {
+    /// The wiring of the display, if the line is enough to know it
+    pub wiring: Option<Wiring>,
+    pub symbols: String,
+}
+
+impl Decoded {
+    /// The outputs read as a number in some base, if they are all digits
+    pub fn value(&self, radix: u32) -> Option<u64> {
+        self.symbols.chars().try_fold(0_u64, |n, c| {
+            let digit = c.to_digit(radix)?;
+            n.checked_mul(radix as u64)?.checked_add(digit as u64)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Rng;
+
+    const SAMPLE: &str =
+        "acedgfb cdfbe gcdfa fbcad dab cefabd cdfgeb eafb cagedb ab | cdfeb fcadb cdfeb cdbaf";
+
+    fn line(s: &str) -> Line {
+        Alphabet::seven_segment().parse_line(s).unwrap()
+    }
+
+    fn decode(s: &str) -> Result<Decoded, LineError> {
+        Alphabet::seven_segment().decode(&line(s))
+    }
+
+    #[test]
+    fn parse_patterns() {
+        let alphabet = Alphabet::seven_segment();
+        assert_eq!(alphabet.parse_pattern("acd"), Some(13));
+        assert_eq!(alphabet.parse_pattern("aa"), None);
+        assert_eq!(alphabet.parse_pattern("ah"), None);
+        assert_eq!(alphabet.parse_pattern(""), None);
+
+        let sample = line(SAMPLE);
+        assert_eq!(sample.patterns.len(), 10);
+        assert_eq!(sample.outputs.len(), 4);
+        assert_eq!(alphabet.parse_line("ab cd"), Err(5));
+        assert_eq!(alphabet.parse_line("ab  cd | ah"), Err(9));
+        assert_eq!(alphabet.parse_line(" aa | ab"), Err(1));
+        assert_eq!(line(" | ab").patterns, []);
+    }
+
+    #[test]
+    fn load_glyph_tables() {
+        let alphabet = Alphabet::parse("# two segments\n\n+ ab\n- a\n").unwrap();
+        assert_eq!(alphabet.segments(), 2);
+        assert_eq!(alphabet.symbol(0b11), Some('+'));
+        assert_eq!(alphabet.glyph('-'), Some(0b01));
+        assert_eq!(alphabet.symbol(0b10), None);
+
+        let error = |table| Alphabet::parse(table).unwrap_err();
+        assert_eq!(error("x"), GlyphError::InvalidLine { line: 1 });
+        assert_eq!(error("xy a"), GlyphError::InvalidLine { line: 1 });
+        assert_eq!(error("x a\ny aB"), GlyphError::InvalidLine { line: 2 });
+        assert_eq!(
+            error("x a\ny b\nx c"),
+            GlyphError::DuplicateSymbol {
+                line: 3,
+                symbol: 'x'
+            }
+        );
+        assert_eq!(
+            error("x ab\ny ba"),
+            GlyphError::SameGlyph {
+                line: 2,
+                symbol: 'y',
+                other: 'x'
+            }
+        );
+        assert_eq!(error("# nothing"), GlyphError::Empty);
+
+        assert_eq!(Alphabet::seven_segment().segments(), 7);
+        assert_eq!(Alphabet::hexadecimal().segments(), 7);
+        assert_eq!(Alphabet::fourteen_segment().segments(), 14);
+        assert_eq!(Alphabet::sixteen_segment().segments(), 16);
+    }
+
+    #[test]
+    fn find_the_full_wiring() {
+        let decoded = decode(SAMPLE).unwrap();
+        let wiring = decoded.wiring.clone().unwrap();
+        assert_eq!(wiring.to_string(), "cfgabde");
+        assert_eq!(wiring.segment(3), 0);
+        assert_eq!(decoded.symbols, "5353");
+        assert_eq!(decoded.value(10), Some(5353));
+
+        let alphabet = Alphabet::seven_segment();
+        let digits: String = line(SAMPLE)
+            .patterns
+            .iter()
+            .map(|p| alphabet.symbol(wiring.apply(*p)).unwrap())
+            .collect();
+        assert_eq!(digits, "8523796401");
+    }
+
+    #[test]
+    fn decode_with_some_patterns_missing() {
+        // the 1, 4 and 7 with the outputs are enough to find the wiring
+        let decoded = decode("dab eafb ab | cdfeb fcadb").unwrap();
+        assert_eq!(decoded.value(10), Some(53));
+        assert_eq!(decoded.wiring.unwrap().to_string(), "cfgabde");
+
+        // the wiring is unknown, but a 1 is always a 1
+        let decoded = decode("dab ab | ab ba").unwrap();
+        assert_eq!(decoded.value(10), Some(11));
+        assert_eq!(decoded.wiring, None);
+        assert!(Alphabet::seven_segment().wirings(&[0b1011, 0b11], 10).len() > 1);
+    }
+
+    #[test]
+    fn report_inconsistent_and_ambiguous_lines() {
+        // two different 1s, or two different 7s
+        assert_eq!(decode("ab ac | ab"), Err(LineError::Inconsistent));
+        assert_eq!(decode("abc abd | ab"), Err(LineError::Inconsistent));
+        // five lit wires may be a 2, a 3 or a 5
+        match decode("| abcde") {
+            Err(LineError::Ambiguous { readings }) => assert_eq!(readings.len(), 2),
+            res => panic!("unexpected {:?}", res),
+        }
+    }
+
+    /// The patterns showing each symbol on a display with shuffled wires
+    fn scramble(alphabet: &Alphabet, rng: &mut Rng) -> (Wiring, Vec<Pattern>) {
+        let mut segments: Vec<usize> = (0..alphabet.segments()).collect();
+        rng.shuffle(&mut segments);
+        let wiring = Wiring { segments };
+        let mut wire_of = vec![0; alphabet.segments()];
+        for (w, s) in wiring.segments.iter().enumerate() {
+            wire_of[*s] = w;
+        }
+        let patterns = alphabet
+            .glyphs
+            .iter()
+            .map(|(_, g)| {
+                (0..alphabet.segments())
+                    .filter(|s| g & 1 << s != 0)
+                    .fold(0, |acc, s| acc | 1 << wire_of[s])
+            })
+            .collect();
+        (wiring, patterns)
+    }
+
+    #[test]
+    fn every_scrambling_of_all_symbols_is_solved() {
+        let alphabets = [
+            Alphabet::seven_segment(),
+            Alphabet::hexadecimal(),
+            Alphabet::fourteen_segment(),
+            Alphabet::sixteen_segment(),
+        ];
+        let mut rng = Rng::new(3);
+        for alphabet in alphabets.iter() {
+            for _ in 0..10 {
+                let (wiring, patterns) = scramble(alphabet, &mut rng);
+                assert_eq!(alphabet.wirings(&patterns, 2), [wiring]);
+            }
+        }
+    }
+
+    #[test]
+    fn read_words_on_scrambled_displays() {
+        let mut rng = Rng::new(4);
+        for alphabet in [Alphabet::fourteen_segment(), Alphabet::sixteen_segment()].iter() {
+            let (wiring, patterns) = scramble(alphabet, &mut rng);
+            // "[" and "]" are not on fourteen-segment displays
+            let known = |c: &char| alphabet.glyph(*c).is_some();
+            let pattern = |c| {
+                let i = alphabet.glyphs.iter().position(|(s, _)| *s == c).unwrap();
+                patterns[i]
+            };
+            let word = |w: &str| {
+                w.chars()
+                    .filter(known)
+                    .map(pattern)
+                    .collect::<Vec<Pattern>>()
+            };
+            // a few words show enough of the wiring to read another one
+            let clues = [
+                "SUBMARINE",
+                "QUICK",
+                "BROWN",
+                "FOX",
+                "JUMPS",
+                "LAZY",
+                "DOG",
+                "[2021]",
+            ];
+            let line = Line {
+                patterns: clues.iter().flat_map(|w| word(w)).collect(),
+                outputs: word("HELLO"),
+            };
+            let decoded = alphabet.decode(&line).unwrap();
+            assert_eq!(decoded.symbols, "HELLO");
+            assert_eq!(decoded.wiring, Some(wiring));
+            // alone, an "E" might be any symbol with as many segments
+            let line = Line {
+                patterns: vec![],
+                outputs: word("E"),
+            };
+            assert!(alphabet.decode(&line).is_err());
+        }
+    }
+
+    #[test]
+    fn read_hexadecimal_values() {
+        let alphabet = Alphabet::hexadecimal();
+        let mut rng = Rng::new(5);
+        let (_, patterns) = scramble(&alphabet, &mut rng);
+        let outputs = [12, 0, 15, 15, 14, 14]
+            .iter()
+            .map(|i| patterns[*i])
+            .collect();
+        let line = Line { patterns, outputs };
+        let decoded = alphabet.decode(&line).unwrap();
+        assert_eq!(decoded.symbols, "C0FFEE");
+        assert_eq!(decoded.value(16), Some(0xC0FFEE));
+        assert_eq!(decoded.value(10), None);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::parser::Move;
+use std::fmt;
+use std::fmt::Display;
+use std::io;
+use std::io::Write;
+
+/// Position, depth and aim of the submarine
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct State {
+    pub position: i32,
+    pub depth: i32,
+    pub aim: i32,
+}
+
+/// The rules giving the state of the submarine after some command
+pub trait Semantics {
+    fn apply(&self, state: State, mv: Move, units: i32) -> State;
+}
+
+/// Up and down change the depth, forward changes the position
+#[derive(Debug, Clone, Copy)]
+pub struct Plain;
+
+impl Semantics for Plain {
+    fn apply(&self, s: State, mv: Move, units: i32) -> State {
+        match mv {
+            Move::Up => State {
+                depth: s.depth - units,
+                ..s
+            },
+            Move::Down => State {
+                depth: s.depth + units,
+                ..s
+            },
+            Move::Forward => State {
+                position: s.position + units,
+                ..s
+            },
+        }
+    }
+}
+
+/// Up and down change the aim, forward moves along the aim
+#[derive(Debug, Clone, Copy)]
+pub struct Aim;
+
+impl Semantics for Aim {
+    fn apply(&self, s: State, mv: Move, units: i32) -> State {
+        match mv {
+            Move::Up => State {
+                aim: s.aim - units,
+                ..s
+            },
+            Move::Down => State {
+                aim: s.aim + units,
+                ..s
+            },
+            Move::Forward => State {
+                position: s.position + units,
+                depth: s.depth + s.aim * units,
+                ..s
+            },
+        }
+    }
+}
+
+/// A command rejected in strict mode because it would take the submarine
+/// above the surface
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NegativeDepth {
+    /// Index of the command, starting at 0
+    pub command: usize,
+    pub mv: Move,
+    pub units: i32,
+    /// State before the command
+    pub state: State,
+    /// The negative depth the command leads to
+    pub depth: i32,
+}
+
+impl Display for NegativeDepth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "command {} ({:?} {}) would take the submarine from depth {} to {}, above the surface",
+            self.command, self.mv, self.units, self.state.depth, self.depth
+        )
+    }
+}
+
+/// Run navigation commands with some semantics, recording the state of the
+/// submarine after each of them
+pub struct Submarine<S: Semantics> {
+    semantics: S,
+    strict: bool,
+    trajectory: Vec<State>,
+    /// Number of commands run so far, rejected ones included
+    commands: usize,
+}
+
+impl<S: Semantics> Submarine<S> {
+    pub fn new(semantics: S) -> Submarine<S> {
+        Submarine {
+            semantics,
+            strict: false,
+            trajectory: vec![],
+            commands: 0,
+        }
+    }
+
+    /// Reject the commands which would make the depth negative
+    pub fn strict(mut self) -> Submarine<S> {
+        self.strict = true;
+        self
+    }
+
+    /// The current state, at the surface if no command ran yet
+    pub fn state(&self) -> State {
+        self.trajectory.last().copied().unwrap_or_default()
+    }
+
+    /// Every state reached so far, one per accepted command
+    pub fn trajectory(&self) -> &[State] {
+        &self.trajectory
+    }
+
+    /// Run a single command. In strict mode a command leading to a negative
+    /// depth is rejected and the state left unchanged.
+    pub fn run(&mut self, mv: Move, units: i32) -> Result<State, NegativeDepth> {
+        let state = self.state();
+        let next = self.semantics.apply(state, mv, units);
+        let command = self.commands;
+        self.commands += 1;
+        if self.strict && next.depth < 0 {
+            return Err(NegativeDepth {
+                command,
+                mv,
+                units,
+                state,
+                depth: next.depth,
+            });
+        }
+        self.trajectory.push(next);
+        Ok(next)
+    }
+
+    /// Run commands in order, stopping at the first rejected one
+    pub fn run_all<I: IntoIterator<Item = (Move, i32)>>(
+        &mut self,
+        commands: I,
+    ) -> Result<State, NegativeDepth> {
+        for (mv, units) in commands {
+            self.run(mv, units)?;
+        }
+        Ok(self.state())
+    }
+
+    /// Write the trajectory as CSV, with a header line
+    pub fn write_csv<W: Write>(&self, mut out: W) -> io::Result<()> {
+        writeln!(out, "command,position,depth,aim")?;
+        for (i, s) in self.trajectory.iter().enumerate() {
+            writeln!(out, "{},{},{},{}", i, s.position, s.depth, s.aim)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_move;
+
+    fn sample() -> Vec<(Move, i32)> {
+        [
+            "forward 5",
+            "down 5",
+            "forward 8",
+            "up 3",
+            "down 8",
+            "forward 2",
+        ]
+        .iter()
+        .map(|l| parse_move(l).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn run_sample_with_both_semantics() {
+        let plain = Submarine::new(Plain).run_all(sample()).unwrap();
+        let aim = Submarine::new(Aim).run_all(sample()).unwrap();
+
+        assert_eq!(plain.position * plain.depth, 150);
+        assert_eq!(aim.position * aim.depth, 900);
+    }
+
+    #[test]
+    fn export_trajectory_as_csv() {
+        let mut sub = Submarine::new(Aim);
+        sub.run_all(sample().into_iter().take(3)).unwrap();
+        let mut csv = vec![];
+        sub.write_csv(&mut csv).unwrap();
+
+        assert_eq!(sub.trajectory().len(), 3);
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "command,position,depth,aim\n0,5,0,0\n1,5,0,5\n2,13,40,5\n"
+        );
+    }
+
+    #[test]
+    fn strict_mode_rejects_negative_depth() {
+        let commands = vec![(Move::Down, 2), (Move::Up, 3), (Move::Forward, 1)];
+
+        let lenient = Submarine::new(Plain).run_all(commands.clone());
+        assert_eq!(lenient.map(|s| s.depth), Ok(-1));
+
+        let mut sub = Submarine::new(Plain).strict();
+        let err = sub.run_all(commands).unwrap_err();
+        assert_eq!(err.command, 1);
+        assert_eq!(err.state.depth, 2);
+        assert_eq!(err.depth, -1);
+        assert!(err
+            .to_string()
+            .ends_with("from depth 2 to -1, above the surface"));
+        assert_eq!(sub.state().depth, 2);
+
+        let mut aim = Submarine::new(Aim).strict();
+        assert!(aim.run(Move::Up, 1).is_ok());
+        assert!(aim.run(Move::Forward, 1).is_err());
+    }
+
+    #[test]
+    fn number_every_command_run() {
+        let mut sub = Submarine::new(Plain).strict();
+        sub.run(Move::Down, 1).unwrap();
+        assert_eq!(sub.run(Move::Up, 2).map_err(|e| e.command), Err(1));
+        assert_eq!(sub.run(Move::Up, 3).map_err(|e| e.command), Err(2));
+        sub.run(Move::Up, 1).unwrap();
+        let err = sub.run(Move::Up, 1).unwrap_err();
+        assert_eq!((err.command, err.depth), (4, -1));
+        assert_eq!(sub.trajectory().len(), 2);
+    }
+}
@@ -0,0 +1,641 @@
+//! Exact intersections of vents in any direction, found with a
+//! Bentley–Ottmann sweep: the vents crossing a vertical sweep line are kept
+//! sorted from bottom to top, and only neighbours on that line are checked
+//! for intersections, so that the cost depends on the number of vents and of
+//! intersections rather than on the number of pairs of vents.
+//!
+//! Collinear vents are first merged into disjoint pieces per line, their
+//! overlaps being computed separately, so that the sweep never sees two
+//! overlapping segments.
+use crate::generators::Rng;
+use crate::y2021::vents::{line, Line, Pos, Vent};
+use num::integer::gcd;
+use num::{BigInt, BigRational, ToPrimitive, Zero};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::fmt::Display;
+
+/// A point with rational coordinates, ordered by x then y as met by the sweep
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Point {
+    pub x: BigRational,
+    pub y: BigRational,
+}
+
+impl Point {
+    fn new(x: BigRational, y: BigRational) -> Point {
+        Point { x, y }
+    }
+
+    /// The position of the point if both its coordinates are integers
+    pub fn to_pos(&self) -> Option<Pos> {
+        if self.x.is_integer() && self.y.is_integer() {
+            Some(Pos {
+                x: self.x.to_integer().to_i64()?,
+                y: self.y.to_integer().to_i64()?,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+impl From<Pos> for Point {
+    fn from(pos: Pos) -> Point {
+        let int = |n: i64| BigRational::from_integer(BigInt::from(n));
+        Point::new(int(pos.x), int(pos.y))
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+/// A point of the sweep, as numerators over a common positive denominator
+/// in lowest terms, so that points are compared without big numbers while
+/// they fit in 128 bits, which they do for ends of vents
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Spot {
+    x: i128,
+    y: i128,
+    d: i128,
+}
+
+impl Spot {
+    fn new(x: i128, y: i128, d: i128) -> Spot {
+        let n = gcd(gcd(x, y), d);
+        Spot {
+            x: x / n,
+            y: y / n,
+            d: d / n,
+        }
+    }
+
+    fn to_point(self) -> Point {
+        let ratio = |n: i128| BigRational::new(BigInt::from(n), BigInt::from(self.d));
+        Point::new(ratio(self.x), ratio(self.y))
+    }
+}
+
+impl From<Pos> for Spot {
+    fn from(pos: Pos) -> Spot {
+        Spot {
+            x: pos.x.into(),
+            y: pos.y.into(),
+            d: 1,
+        }
+    }
+}
+
+/// Compare `a / b` and `c / d` for positive `b` and `d`
+fn cmp_fractions(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    if b == d {
+        return a.cmp(&c);
+    }
+    match (a.checked_mul(d), c.checked_mul(b)) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        _ => (BigInt::from(a) * BigInt::from(d)).cmp(&(BigInt::from(c) * BigInt::from(b))),
+    }
+}
+
+impl Ord for Spot {
+    fn cmp(&self, other: &Spot) -> Ordering {
+        cmp_fractions(self.x, self.d, other.x, other.d)
+            .then_with(|| cmp_fractions(self.y, self.d, other.y, other.d))
+    }
+}
+
+impl PartialOrd for Spot {
+    fn partial_cmp(&self, other: &Spot) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// A point where vents of different lines meet, with the indices of all the
+/// vents going through it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crossing {
+    pub point: Point,
+    pub vents: Vec<usize>,
+}
+
+/// A sub-segment shared by the same collinear vents all along, possibly a
+/// single point where collinear vents touch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Overlap {
+    pub from: Pos,
+    pub to: Pos,
+    pub vents: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Intersections {
+    /// In sweep order
+    pub crossings: Vec<Crossing>,
+    /// Sorted by ends
+    pub overlaps: Vec<Overlap>,
+}
+
+/// A vent with its ends in sweep order
+#[derive(Debug, Clone, Copy)]
+struct Span {
+    lo: Pos,
+    hi: Pos,
+    vent: usize,
+}
+
+/// Disjoint piece of a line covered by collinear vents, which is what the
+/// sweep works on, with the vents making it sorted by their lowest end
+#[derive(Debug, Clone)]
+struct Segment {
+    a: Pos,
+    b: Pos,
+    spans: Vec<Span>,
+}
+
+impl Segment {
+    fn dx(&self) -> i128 {
+        (self.b.x - self.a.x).into()
+    }
+
+    fn dy(&self) -> i128 {
+        (self.b.y - self.a.y).into()
+    }
+
+    /// Whether the segment is below, through or above a point at the same x
+    fn side(&self, p: &Spot) -> Ordering {
+        let (ax, ay) = (i128::from(self.a.x), i128::from(self.a.y));
+        if self.a.x == self.b.x {
+            return if cmp_fractions(p.y, p.d, ay, 1) == Ordering::Less {
+                Ordering::Greater
+            } else if cmp_fractions(p.y, p.d, self.b.y.into(), 1) == Ordering::Greater {
+                Ordering::Less
+            } else {
+                Ordering::Equal
+            };
+        }
+        // the point is above the segment when turning left from it, which is
+        // the sign of dx * (y - ay) - dy * (x - ax), times d
+        let orient = || {
+            let ey = p.y.checked_sub(ay.checked_mul(p.d)?)?;
+            let ex = p.x.checked_sub(ax.checked_mul(p.d)?)?;
+            self.dx()
+                .checked_mul(ey)?
+                .checked_sub(self.dy().checked_mul(ex)?)
+        };
+        match orient() {
+            Some(orient) => 0.cmp(&orient),
+            None => {
+                let big = BigInt::from;
+                let ey = big(p.y) - big(ay) * big(p.d);
+                let ex = big(p.x) - big(ax) * big(p.d);
+                BigInt::zero().cmp(&(big(self.dx()) * ey - big(self.dy()) * ex))
+            }
+        }
+    }
+
+    /// Order of two segments going through the same point, just after it:
+    /// by increasing slope, vertical last
+    fn after(&self, other: &Segment) -> Ordering {
+        (other.dx() * self.dy()).cmp(&(self.dx() * other.dy()))
+    }
+
+    /// The vents of the segment going through a point of it
+    fn vents_at(&self, p: Spot) -> impl Iterator<Item = usize> + '_ {
+        self.spans
+            .iter()
+            .take_while(move |s| Spot::from(s.lo) <= p)
+            .filter(move |s| Spot::from(s.hi) >= p)
+            .map(|s| s.vent)
+    }
+}
+
+/// The point where two non parallel segments meet, if any
+fn intersection(s: &Segment, t: &Segment) -> Option<Spot> {
+    let denom = s.dx() * t.dy() - s.dy() * t.dx();
+    if denom == 0 {
+        return None;
+    }
+    let (ex, ey) = (i128::from(t.a.x - s.a.x), i128::from(t.a.y - s.a.y));
+    // s.a + num / denom * (s.b - s.a) == t.a + other / denom * (t.b - t.a)
+    let (mut num, mut other) = (ex * t.dy() - ey * t.dx(), ex * s.dy() - ey * s.dx());
+    let mut denom = denom;
+    if denom < 0 {
+        num = -num;
+        other = -other;
+        denom = -denom;
+    }
+    if num < 0 || num > denom || other < 0 || other > denom {
+        return None;
+    }
+    let coordinate = |a: i64, d: i128| i128::from(a) * denom + num * d;
+    Some(Spot::new(
+        coordinate(s.a.x, s.dx()),
+        coordinate(s.a.y, s.dy()),
+        denom,
+    ))
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    segment: usize,
+    priority: u64,
+    left: Option<usize>,
+    right: Option<usize>,
+}
+
+/// The segments crossing the sweep line from bottom to top, in a treap
+/// split and merged around each event point
+struct Status {
+    nodes: Vec<Node>,
+    rng: Rng,
+}
+
+type Tree = Option<usize>;
+
+impl Status {
+    fn node(&mut self, segment: usize) -> Tree {
+        let priority = self.rng.next_u64();
+        self.nodes.push(Node {
+            segment,
+            priority,
+            left: None,
+            right: None,
+        });
+        Some(self.nodes.len() - 1)
+    }
+
+    /// Split a tree into the segments for which `left` holds, which must
+    /// come first, and the other ones
+    fn split(&mut self, tree: Tree, left: &dyn Fn(usize) -> bool) -> (Tree, Tree) {
+        match tree {
+            None => (None, None),
+            Some(n) => {
+                if left(self.nodes[n].segment) {
+                    let (l, r) = self.split(self.nodes[n].right, left);
+                    self.nodes[n].right = l;
+                    (Some(n), r)
+                } else {
+                    let (l, r) = self.split(self.nodes[n].left, left);
+                    self.nodes[n].left = r;
+                    (l, Some(n))
+                }
+            }
+        }
+    }
+
+    fn merge(&mut self, a: Tree, b: Tree) -> Tree {
+        match (a, b) {
+            (None, t) | (t, None) => t,
+            (Some(x), Some(y)) => {
+                if self.nodes[x].priority > self.nodes[y].priority {
+                    self.nodes[x].right = self.merge(self.nodes[x].right, b);
+                    Some(x)
+                } else {
+                    self.nodes[y].left = self.merge(a, self.nodes[y].left);
+                    Some(y)
+                }
+            }
+        }
+    }
+
+    fn first(&self, mut tree: Tree) -> Option<usize> {
+        while let Some(n) = tree {
+            match self.nodes[n].left {
+                Some(_) => tree = self.nodes[n].left,
+                None => return Some(self.nodes[n].segment),
+            }
+        }
+        None
+    }
+
+    fn last(&self, mut tree: Tree) -> Option<usize> {
+        while let Some(n) = tree {
+            match self.nodes[n].right {
+                Some(_) => tree = self.nodes[n].right,
+                None => return Some(self.nodes[n].segment),
+            }
+        }
+        None
+    }
+
+    fn segments(&self, tree: Tree, res: &mut Vec<usize>) {
+        if let Some(n) = tree {
+            self.segments(self.nodes[n].left, res);
+            res.push(self.nodes[n].segment);
+            self.segments(self.nodes[n].right, res);
+        }
+    }
+}
+
+/// Group collinear vents by line, and compute their overlaps and the
+/// disjoint pieces they cover
+fn pieces(vents: &[Vent]) -> (Vec<Segment>, Vec<Overlap>) {
+    let mut lines: HashMap<Line, Vec<Span>> = HashMap::new();
+    for (i, vent) in vents.iter().enumerate() {
+        let (lo, hi) = (vent.from().min(vent.to()), vent.from().max(vent.to()));
+        let span = Span { lo, hi, vent: i };
+        lines.entry(line(vent).0).or_default().push(span);
+    }
+    let (mut segments, mut overlaps) = (vec![], vec![]);
+    for (_, mut spans) in lines {
+        spans.sort_by_key(|s| (s.lo, s.hi));
+        // ends of the vents, with the vents starting and ending there
+        let mut ends: BTreeMap<Pos, (Vec<usize>, Vec<usize>)> = BTreeMap::new();
+        for s in &spans {
+            ends.entry(s.lo).or_default().0.push(s.vent);
+            ends.entry(s.hi).or_default().1.push(s.vent);
+        }
+        // cut the line at the ends, and into the open pieces between them
+        // which are covered, with the vents covering each cut
+        let mut cuts: Vec<(Pos, Pos, BTreeSet<usize>)> = vec![];
+        let mut active = BTreeSet::new();
+        let mut prev = None;
+        for (pos, (starting, ending)) in ends {
+            if let Some(prev) = prev {
+                if !active.is_empty() {
+                    cuts.push((prev, pos, active.clone()));
+                }
+            }
+            active.extend(starting);
+            cuts.push((pos, pos, active.clone()));
+            for v in ending {
+                active.remove(&v);
+            }
+            prev = Some(pos);
+        }
+
+        let mut line_segments: Vec<Segment> = vec![];
+        let mut run: Option<Overlap> = None;
+        for (from, to, covering) in cuts {
+            match line_segments.last_mut() {
+                Some(segment) if segment.b == from => segment.b = to,
+                _ => line_segments.push(Segment {
+                    a: from,
+                    b: to,
+                    spans: vec![],
+                }),
+            }
+            let vents: Vec<usize> = covering.into_iter().collect();
+            match run.as_mut() {
+                Some(o) if o.vents == vents && o.to == from => o.to = to,
+                _ => {
+                    overlaps.extend(run.take());
+                    if vents.len() > 1 {
+                        run = Some(Overlap { from, to, vents });
+                    }
+                }
+            }
+        }
+        overlaps.extend(run);
+
+        let mut j = 0;
+        for span in spans {
+            while line_segments[j].b < span.lo {
+                j += 1;
+            }
+            line_segments[j].spans.push(span);
+        }
+        segments.extend(line_segments);
+    }
+    overlaps.sort_by_key(|o| (o.from, o.to));
+    (segments, overlaps)
+}
+
+/// Every point where vents of different lines meet, with exact rational
+/// coordinates, and every sub-segment shared by collinear vents.
+pub fn intersect(vents: &[Vent]) -> Intersections {
+    let (segments, overlaps) = pieces(vents);
+    // event points, with the segments starting there
+    let mut events: BTreeMap<Spot, Vec<usize>> = BTreeMap::new();
+    for (i, s) in segments.iter().enumerate() {
+        events.entry(Spot::from(s.a)).or_default().push(i);
+        events.entry(Spot::from(s.b)).or_default();
+    }
+    let mut status = Status {
+        nodes: vec![],
+        rng: Rng::new(0),
+    };
+    let mut root: Tree = None;
+    let mut crossings = vec![];
+
+    while let Some((p, starting)) = events.pop_first() {
+        let (below, rest) = status.split(root, &|s| segments[s].side(&p) == Ordering::Less);
+        let (through, above) = status.split(rest, &|s| segments[s].side(&p) == Ordering::Equal);
+        let mut here = vec![];
+        status.segments(through, &mut here);
+        here.extend(starting.iter().copied());
+        if here.len() > 1 {
+            let mut vents: Vec<usize> =
+                here.iter().flat_map(|s| segments[*s].vents_at(p)).collect();
+            vents.sort_unstable();
+            vents.dedup();
+            crossings.push(Crossing {
+                point: p.to_point(),
+                vents,
+            });
+        }
+
+        // the segments going on after the point, in their new order
+        let mut after: Vec<usize> = here
+            .into_iter()
+            .filter(|s| Spot::from(segments[*s].b) != p)
+            .collect();
+        after.sort_by(|s, t| segments[*s].after(&segments[*t]));
+        let mut middle = None;
+        for s in &after {
+            let node = status.node(*s);
+            middle = status.merge(middle, node);
+        }
+        let (lower, upper) = (status.last(below), status.first(above));
+        let mut check = |s: Option<usize>, t: Option<usize>| {
+            if let (Some(s), Some(t)) = (s, t) {
+                if let Some(q) = intersection(&segments[s], &segments[t]) {
+                    if q > p {
+                        events.entry(q).or_default();
+                    }
+                }
+            }
+        };
+        if after.is_empty() {
+            check(lower, upper);
+        } else {
+            check(lower, after.first().copied());
+            check(after.last().copied(), upper);
+        }
+        let left = status.merge(below, middle);
+        root = status.merge(left, above);
+    }
+
+    Intersections {
+        crossings,
+        overlaps,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::y2021::generators;
+    use crate::y2021::vents::{
+        draw_lines, intersections, is_ortho, parse_vent, positions, raster_overlaps, Raster,
+    };
+    use std::collections::HashSet;
+
+    fn parse(input: &str) -> Vec<Vent> {
+        input.split('\n').filter_map(parse_vent).collect()
+    }
+
+    const SAMPLE: &str = "0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2";
+
+    /// Number of integer points on at least two vents
+    fn lattice_overlaps(vents: &[Vent]) -> usize {
+        let res = intersect(vents);
+        let mut points: HashSet<Pos> = res
+            .crossings
+            .iter()
+            .filter_map(|c| c.point.to_pos())
+            .collect();
+        for o in &res.overlaps {
+            points.extend(positions(&Vent::new(o.from, o.to)));
+        }
+        points.len()
+    }
+
+    #[test]
+    fn agree_with_drawn_sample() {
+        let all = parse(SAMPLE);
+        let ortho: Vec<Vent> = all.iter().cloned().filter(is_ortho).collect();
+        for vents in [ortho, all] {
+            let mut board = vec![vec![0; 10]; 10];
+            draw_lines(&mut board, vents.clone()).unwrap();
+
+            assert_eq!(lattice_overlaps(&vents), intersections(&board) as usize);
+        }
+    }
+
+    #[test]
+    fn find_exact_crossings() {
+        let res = intersect(&parse("0,0 -> 3,1\n1,0 -> 1,3\n-1,2 -> 3,2"));
+
+        let points: Vec<String> = res.crossings.iter().map(|c| c.point.to_string()).collect();
+        assert_eq!(points, ["1,1/3", "1,2"]);
+        assert_eq!(res.crossings[0].vents, [0, 1]);
+        assert_eq!(res.crossings[1].vents, [1, 2]);
+        assert_eq!(res.overlaps, []);
+    }
+
+    #[test]
+    fn find_collinear_overlaps() {
+        let res = intersect(&parse(
+            "0,0 -> 4,4\n6,6 -> 2,2\n6,6 -> 8,8\n3,3 -> 3,3\n0,4 -> 4,0",
+        ));
+
+        let overlap = |from: (i64, i64), to: (i64, i64), vents: Vec<usize>| Overlap {
+            from: Pos {
+                x: from.0,
+                y: from.1,
+            },
+            to: Pos { x: to.0, y: to.1 },
+            vents,
+        };
+        assert_eq!(
+            res.overlaps,
+            [
+                overlap((2, 2), (4, 4), vec![0, 1]),
+                overlap((6, 6), (6, 6), vec![1, 2]),
+            ]
+        );
+        // the anti-diagonal crosses the overlap of the first two vents, and
+        // the single point vent has no line of its own to overlap them
+        let crossings: Vec<(Option<Pos>, Vec<usize>)> = res
+            .crossings
+            .into_iter()
+            .map(|c| (c.point.to_pos(), c.vents))
+            .collect();
+        assert_eq!(
+            crossings,
+            [
+                (Some(Pos { x: 2, y: 2 }), vec![0, 1, 4]),
+                (Some(Pos { x: 3, y: 3 }), vec![0, 1, 3]),
+            ]
+        );
+    }
+
+    /// Crossings found by checking every pair of vents
+    fn all_pairs(vents: &[Vent]) -> HashSet<Point> {
+        let segment = |v: &Vent| Segment {
+            a: v.from().min(v.to()),
+            b: v.from().max(v.to()),
+            spans: vec![],
+        };
+        // a single point vent has no direction to cross another one with
+        let meet = |s: &Segment, t: &Segment| {
+            if s.a == s.b {
+                let on = t.a <= s.a && s.a <= t.b && t.side(&s.a.into()) == Ordering::Equal;
+                Some(Spot::from(s.a)).filter(|_| on)
+            } else {
+                intersection(s, t)
+            }
+        };
+        let mut res = HashSet::new();
+        for (i, v) in vents.iter().enumerate() {
+            for w in &vents[i + 1..] {
+                let (s, t) = (segment(v), segment(w));
+                if line(v).0 .0 != line(w).0 .0 {
+                    res.extend(meet(&s, &t).or_else(|| meet(&t, &s)));
+                }
+            }
+        }
+        res.into_iter().map(Spot::to_point).collect()
+    }
+
+    fn crossing_points(vents: &[Vent]) -> HashSet<Point> {
+        intersect(vents)
+            .crossings
+            .into_iter()
+            .map(|c| c.point)
+            .collect()
+    }
+
+    #[test]
+    fn agree_with_all_pairs_on_random_vents() {
+        for seed in 0..40 {
+            let vents = parse(&generators::vents(&mut Rng::new(seed), 25));
+
+            assert_eq!(
+                lattice_overlaps(&vents) as u64,
+                raster_overlaps(&vents, Raster::Lattice)
+            );
+            assert_eq!(crossing_points(&vents), all_pairs(&vents), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn stay_exact_near_the_largest_coordinates() {
+        for seed in 0..10 {
+            let scale = |p: Pos| Pos {
+                x: p.x * 85_000_000 + 7,
+                y: p.y * 85_000_001 - 3,
+            };
+            let vents: Vec<Vent> = parse(&generators::vents(&mut Rng::new(seed), 25))
+                .into_iter()
+                .map(|v| Vent::new(scale(v.from()), scale(v.to())))
+                .collect();
+
+            assert_eq!(crossing_points(&vents), all_pairs(&vents), "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,389 @@
+//! Vents sorted into the buckets of a uniform grid, each bucket holding the
+//! vents going through its cell, to answer queries on points and rectangles
+//! without drawing the vents on a board
+use crate::y2021::vent_crossings::intersect;
+use crate::y2021::vents::{
+    covers, depths, index_of, line, position_at, sweep, Interval, Line, Pos, Vent,
+};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+
+/// Index of a cell of the grid, along x and y
+type Cell = (i64, i64);
+
+pub struct VentIndex {
+    vents: Vec<Vent>,
+    /// Width and height of the cells
+    cell: i64,
+    buckets: HashMap<Cell, Vec<usize>>,
+}
+
+impl VentIndex {
+    /// Index vents with cells as large as the average vent
+    pub fn new(vents: Vec<Vent>) -> VentIndex {
+        let length = |v: &Vent| {
+            let (from, to) = (v.from(), v.to());
+            (to.x - from.x).abs().max((to.y - from.y).abs())
+        };
+        let total: i64 = vents.iter().map(length).sum();
+        let cell = (total / vents.len().max(1) as i64).max(1);
+        VentIndex::with_cell(vents, cell)
+    }
+
+    pub fn with_cell(vents: Vec<Vent>, cell: i64) -> VentIndex {
+        assert!(cell > 0, "cells must not be empty");
+        let mut buckets: HashMap<Cell, Vec<usize>> = HashMap::new();
+        for (i, vent) in vents.iter().enumerate() {
+            for c in cells(vent, cell) {
+                buckets.entry(c).or_default().push(i);
+            }
+        }
+        VentIndex {
+            vents,
+            cell,
+            buckets,
+        }
+    }
+
+    pub fn vents(&self) -> &[Vent] {
+        &self.vents
+    }
+
+    fn cell_of(&self, pos: Pos) -> Cell {
+        (pos.x.div_euclid(self.cell), pos.y.div_euclid(self.cell))
+    }
+
+    /// Indices of the vents going through a position
+    pub fn at(&self, pos: Pos) -> Vec<usize> {
+        self.buckets.get(&self.cell_of(pos)).map_or(vec![], |b| {
+            b.iter()
+                .copied()
+                .filter(|i| self.vents[*i].contains(pos))
+                .collect()
+        })
+    }
+
+    /// Indices of the vents going through a rectangle, given by two opposite
+    /// corners which are part of it
+    pub fn in_rect(&self, a: Pos, b: Pos) -> Vec<usize> {
+        let lo = Pos {
+            x: a.x.min(b.x),
+            y: a.y.min(b.y),
+        };
+        let hi = Pos {
+            x: a.x.max(b.x),
+            y: a.y.max(b.y),
+        };
+        let ((x0, y0), (x1, y1)) = (self.cell_of(lo), self.cell_of(hi));
+        let mut candidates = BTreeSet::new();
+        // walk the cells of the rectangle, or the buckets when there are fewer
+        let covered = (x1 - x0 + 1) as u128 * (y1 - y0 + 1) as u128;
+        if covered <= self.buckets.len() as u128 {
+            for x in x0..=x1 {
+                for y in y0..=y1 {
+                    candidates.extend(self.buckets.get(&(x, y)).into_iter().flatten());
+                }
+            }
+        } else {
+            for ((x, y), bucket) in &self.buckets {
+                if (x0..=x1).contains(x) && (y0..=y1).contains(y) {
+                    candidates.extend(bucket);
+                }
+            }
+        }
+        candidates
+            .into_iter()
+            .filter(|i| crosses_rect(&self.vents[*i], lo, hi))
+            .collect()
+    }
+
+    pub fn count_in_rect(&self, a: Pos, b: Pos) -> usize {
+        self.in_rect(a, b).len()
+    }
+
+    /// Indices of the vents of each line
+    fn lines(&self) -> HashMap<Line, Vec<usize>> {
+        let mut lines: HashMap<Line, Vec<usize>> = HashMap::new();
+        for (i, vent) in self.vents.iter().enumerate() {
+            lines.entry(line(vent).0).or_default().push(i);
+        }
+        lines
+    }
+
+    fn intervals(&self, on_line: &[usize]) -> Vec<Interval> {
+        on_line.iter().map(|i| line(&self.vents[*i]).1).collect()
+    }
+
+    /// Indices of the vents on each position where vents of different lines
+    /// meet
+    fn crossings(&self) -> HashMap<Pos, Vec<usize>> {
+        intersect(&self.vents)
+            .crossings
+            .into_iter()
+            .filter_map(|c| Some((c.point.to_pos()?, c.vents)))
+            .collect()
+    }
+
+    /// The `k` positions on the most vents, with their number of vents, ties
+    /// broken by position. The positions of a line covered by the same
+    /// number of its vents are walked one at a time from a sweep of the
+    /// line, and the crossings of lines are ranked on their own, so that a
+    /// long vent costs no more than a short one.
+    pub fn densest(&self, k: usize) -> Vec<(Pos, usize)> {
+        let crossings = self.crossings();
+        // number of vents, next position and its index, last index, and a
+        // vent to walk the line with, smallest first
+        let mut runs = BinaryHeap::new();
+        for on_line in self.lines().values() {
+            for ((lo, hi), n) in depths(&self.intervals(on_line)) {
+                let vent = on_line[0];
+                let from = position_at(&self.vents[vent], lo);
+                runs.push(Reverse((Reverse(n), from, lo, hi, Some(vent))));
+            }
+        }
+        for (pos, vents) in &crossings {
+            runs.push(Reverse((Reverse(vents.len()), *pos, 0, 0, None)));
+        }
+
+        let mut res = vec![];
+        while res.len() < k {
+            let (n, pos, t, hi, vent) = match runs.pop() {
+                Some(Reverse((Reverse(n), pos, t, hi, vent))) => (n, pos, t, hi, vent),
+                None => break,
+            };
+            match vent {
+                None => res.push((pos, n)),
+                Some(vent) => {
+                    if !crossings.contains_key(&pos) {
+                        res.push((pos, n));
+                    }
+                    if t < hi {
+                        let next = position_at(&self.vents[vent], t + 1);
+                        runs.push(Reverse((Reverse(n), next, t + 1, hi, Some(vent))));
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// For each vent, the number of its positions shared with other vents:
+    /// the ones covered twice on its line, and its other crossings
+    pub fn overlap_counts(&self) -> Vec<usize> {
+        let twice: HashMap<Line, Vec<Interval>> = self
+            .lines()
+            .iter()
+            .map(|(l, on_line)| (*l, sweep(&self.intervals(on_line))))
+            .collect();
+        let mut counts: Vec<usize> = self
+            .vents
+            .iter()
+            .map(|vent| {
+                let (l, (lo, hi)) = line(vent);
+                let covered = &twice[&l];
+                covered[covered.partition_point(|(_, b)| *b < lo)..]
+                    .iter()
+                    .take_while(|(a, _)| *a <= hi)
+                    .map(|(a, b)| (b.min(&hi) - a.max(&lo) + 1) as usize)
+                    .sum()
+            })
+            .collect();
+        for (pos, vents) in self.crossings() {
+            for i in vents {
+                let vent = &self.vents[i];
+                if !covers(&twice[&line(vent).0], index_of(vent, pos)) {
+                    counts[i] += 1;
+                }
+            }
+        }
+        counts
+    }
+}
+
+/// Cells a vent may go through: the cells of each column of the grid
+/// between the lowest and highest y of the vent in that column
+fn cells(vent: &Vent, cell: i64) -> Vec<Cell> {
+    let (a, b) = (vent.from().min(vent.to()), vent.from().max(vent.to()));
+    let floor = |n: i64| n.div_euclid(cell);
+    if a.x == b.x {
+        return (floor(a.y)..=floor(b.y)).map(|y| (floor(a.x), y)).collect();
+    }
+    let (dx, dy) = (i128::from(b.x - a.x), i128::from(b.y - a.y));
+    // lowest integer not above the vent at some x
+    let y_at = |x: i64| a.y + (i128::from(x - a.x) * dy).div_euclid(dx) as i64;
+    let mut res = vec![];
+    for column in floor(a.x)..=floor(b.x) {
+        let x0 = a.x.max(column * cell);
+        let x1 = b.x.min((column + 1) * cell);
+        let (y0, y1) = (y_at(x0), y_at(x1));
+        for row in floor(y0.min(y1))..=floor(y0.max(y1)) {
+            res.push((column, row));
+        }
+    }
+    res
+}
+
+/// Whether a vent goes through the rectangle between two corners: their
+/// bounding boxes overlap and the corners are not all on the same side of it
+fn crosses_rect(vent: &Vent, lo: Pos, hi: Pos) -> bool {
+    let (from, to) = (vent.from(), vent.to());
+    if from.x.max(to.x) < lo.x
+        || from.x.min(to.x) > hi.x
+        || from.y.max(to.y) < lo.y
+        || from.y.min(to.y) > hi.y
+    {
+        return false;
+    }
+    let (dx, dy) = (i128::from(to.x - from.x), i128::from(to.y - from.y));
+    let side =
+        |x: i64, y: i64| (dx * i128::from(y - from.y) - dy * i128::from(x - from.x)).signum();
+    let sides = [
+        side(lo.x, lo.y),
+        side(lo.x, hi.y),
+        side(hi.x, lo.y),
+        side(hi.x, hi.y),
+    ];
+    !(sides.iter().all(|s| *s > 0) || sides.iter().all(|s| *s < 0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::Rng;
+    use crate::y2021::generators;
+    use crate::y2021::vents::{parse_vent, positions, raster_overlaps, Raster};
+
+    fn parse(input: &str) -> Vec<Vent> {
+        input.split('\n').filter_map(parse_vent).collect()
+    }
+
+    fn pos(x: i64, y: i64) -> Pos {
+        Pos { x, y }
+    }
+
+    const SAMPLE: &str = "0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2";
+
+    #[test]
+    fn find_vents_through_a_point() {
+        let index = VentIndex::with_cell(parse(SAMPLE), 3);
+
+        assert_eq!(index.at(pos(7, 4)), [2, 4]);
+        assert_eq!(index.at(pos(4, 4)), [1, 2, 8]);
+        assert_eq!(index.at(pos(9, 9)), []);
+        assert_eq!(index.at(pos(-100, 4)), []);
+    }
+
+    #[test]
+    fn find_vents_through_a_rectangle() {
+        let index = VentIndex::new(parse(SAMPLE));
+
+        assert_eq!(index.in_rect(pos(0, 0), pos(9, 9)).len(), 10);
+        assert_eq!(index.in_rect(pos(3, 8), pos(1, 9)), [0, 6]);
+        assert_eq!(index.in_rect(pos(1, 9), pos(0, 8)), [0, 1, 6]);
+        // only passing between the corners of the rectangle
+        let steep = parse("0,0 -> 3,1");
+        let index = VentIndex::with_cell(steep, 1);
+        assert_eq!(index.count_in_rect(pos(1, 1), pos(1, 5)), 0);
+        assert_eq!(index.count_in_rect(pos(2, 0), pos(2, 0)), 0);
+        assert_eq!(index.count_in_rect(pos(1, 0), pos(2, 1)), 1);
+    }
+
+    #[test]
+    fn buckets_find_the_same_vents_as_a_scan() {
+        for seed in 0..20 {
+            let vents = parse(&generators::vents(&mut Rng::new(seed), 30));
+            let mut rng = Rng::new(seed + 100);
+            for cell in [1, 4, 7, 100] {
+                let index = VentIndex::with_cell(vents.clone(), cell);
+                for _ in 0..20 {
+                    let a = pos(rng.range(-35, 35), rng.range(-35, 35));
+                    let b = pos(rng.range(-35, 35), rng.range(-35, 35));
+                    let lo = pos(a.x.min(b.x), a.y.min(b.y));
+                    let hi = pos(a.x.max(b.x), a.y.max(b.y));
+                    let scan: Vec<usize> = (0..vents.len())
+                        .filter(|i| crosses_rect(&vents[*i], lo, hi))
+                        .collect();
+                    assert_eq!(index.in_rect(a, b), scan);
+
+                    let scan: Vec<usize> =
+                        (0..vents.len()).filter(|i| vents[*i].contains(a)).collect();
+                    assert_eq!(index.at(a), scan);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rank_positions_by_number_of_vents() {
+        let vents = parse(SAMPLE);
+        let index = VentIndex::new(vents.clone());
+        let overlaps = raster_overlaps(&vents, Raster::Lattice) as usize;
+
+        let densest = index.densest(overlaps + 1);
+        assert!(densest[..overlaps].iter().all(|(_, n)| *n >= 2));
+        assert_eq!(densest[overlaps].1, 1);
+        assert_eq!(index.densest(2), [(pos(4, 4), 3), (pos(6, 4), 3)]);
+    }
+
+    /// Number of vents on each of their positions, drawing all of them
+    fn coverage(vents: &[Vent]) -> HashMap<Pos, usize> {
+        let mut counts = HashMap::new();
+        for vent in vents {
+            for pos in positions(vent) {
+                *counts.entry(pos).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn same_ranks_as_drawing_the_vents() {
+        for seed in 0..20 {
+            let vents = parse(&generators::vents(&mut Rng::new(seed), 30));
+            let counts = coverage(&vents);
+            let mut ranked: Vec<(Pos, usize)> = counts.clone().into_iter().collect();
+            ranked.sort_unstable_by_key(|(pos, n)| (Reverse(*n), *pos));
+            let shared: Vec<usize> = vents
+                .iter()
+                .map(|v| positions(v).iter().filter(|p| counts[p] > 1).count())
+                .collect();
+
+            let index = VentIndex::new(vents);
+            assert_eq!(index.densest(ranked.len() + 1), ranked);
+            assert_eq!(index.densest(7), ranked[..7]);
+            assert_eq!(index.overlap_counts(), shared);
+        }
+    }
+
+    #[test]
+    fn rank_along_very_long_vents() {
+        let index = VentIndex::new(parse(
+            "0,0 -> 1000000000,0\n10,0 -> 0,0\n5,-5 -> 5,5\n999999999,-1 -> 999999999,1",
+        ));
+
+        assert_eq!(
+            index.densest(3),
+            [(pos(5, 0), 3), (pos(0, 0), 2), (pos(1, 0), 2)]
+        );
+        let densest = index.densest(13);
+        assert_eq!(densest[11], (pos(999999999, 0), 2));
+        assert_eq!(densest[12], (pos(5, -5), 1));
+        assert_eq!(index.overlap_counts(), [12, 11, 1, 1]);
+    }
+
+    #[test]
+    fn count_overlapping_positions_of_each_vent() {
+        let index = VentIndex::new(parse("0,0 -> 4,0\n6,0 -> 2,0\n3,-1 -> 3,5\n9,9 -> 9,9"));
+
+        assert_eq!(index.overlap_counts(), [3, 3, 1, 0]);
+    }
+}
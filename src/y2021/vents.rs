@@ -0,0 +1,667 @@
+use crate::parser::signed;
+use crate::y2021::vent_crossings::intersect;
+use nom::bytes::complete::tag;
+use nom::character::complete::char;
+use nom::character::complete::space1;
+use nom::combinator::map;
+use nom::combinator::map_res;
+use nom::error::Error;
+use nom::sequence::tuple;
+use nom::Err;
+use nom::IResult;
+use num::integer::gcd;
+use std::cmp::max;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::fs::read_to_string;
+
+type E<'a> = Err<Error<&'a str>>;
+
+/// A position on the ocean floor. Parsed coordinates fit in 32 bits.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
+pub struct Pos {
+    pub x: i64,
+    pub y: i64,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Vent {
+    from: Pos,
+    to: Pos,
+}
+
+impl Vent {
+    pub fn new(from: Pos, to: Pos) -> Vent {
+        Vent { from, to }
+    }
+
+    pub fn from(&self) -> Pos {
+        self.from
+    }
+
+    pub fn to(&self) -> Pos {
+        self.to
+    }
+
+    /// Whether a position lies on the vent
+    pub fn contains(&self, pos: Pos) -> bool {
+        let (dx, dy) = (
+            i128::from(self.to.x - self.from.x),
+            i128::from(self.to.y - self.from.y),
+        );
+        let (ex, ey) = (
+            i128::from(pos.x - self.from.x),
+            i128::from(pos.y - self.from.y),
+        );
+        let within = |p: i64, a: i64, b: i64| a.min(b) <= p && p <= a.max(b);
+        dx * ey == dy * ex
+            && within(pos.x, self.from.x, self.to.x)
+            && within(pos.y, self.from.y, self.to.y)
+    }
+}
+
+/// How a vent is turned into positions on a grid
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Raster {
+    /// The points of the vent with integer coordinates only
+    Lattice,
+    /// One position per unit along the longest axis, the closest to the vent
+    Bresenham,
+}
+
+/// Smallest integer step from one end of a vent to the other, and the
+/// number of such steps
+fn step(vent: &Vent) -> ((i64, i64), i64) {
+    let (dx, dy) = (vent.to.x - vent.from.x, vent.to.y - vent.from.y);
+    let n = gcd(dx, dy);
+    if n == 0 {
+        ((0, 0), 0)
+    } else {
+        ((dx / n, dy / n), n)
+    }
+}
+
+fn lattice(vent: &Vent) -> Vec<Pos> {
+    let ((dx, dy), n) = step(vent);
+    (0..=n)
+        .map(|k| Pos {
+            x: vent.from.x + k * dx,
+            y: vent.from.y + k * dy,
+        })
+        .collect()
+}
+
+fn bresenham(vent: &Vent) -> Vec<Pos> {
+    let (dx, dy) = (
+        (vent.to.x - vent.from.x).abs(),
+        -(vent.to.y - vent.from.y).abs(),
+    );
+    let sx = if vent.from.x < vent.to.x { 1 } else { -1 };
+    let sy = if vent.from.y < vent.to.y { 1 } else { -1 };
+    let mut err = dx + dy;
+    let mut pos = vent.from;
+    let mut res = vec![pos];
+    while pos != vent.to {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            pos.x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            pos.y += sy;
+        }
+        res.push(pos);
+    }
+    res
+}
+
+/// Positions of a vent from one end to the other. Both modes give the same
+/// positions for horizontal, vertical and diagonal vents.
+pub fn rasterize(vent: &Vent, raster: Raster) -> Vec<Pos> {
+    match raster {
+        Raster::Lattice => lattice(vent),
+        Raster::Bresenham => bresenham(vent),
+    }
+}
+
+/// Points of a vent with integer coordinates, from one end to the other
+pub fn positions(vent: &Vent) -> Vec<Pos> {
+    rasterize(vent, Raster::Lattice)
+}
+
+pub fn is_ortho(vent: &Vent) -> bool {
+    vent.from.x == vent.to.x || vent.from.y == vent.to.y
+}
+
+/// Whether a vent is at 45 degrees
+pub fn is_diagonal(vent: &Vent) -> bool {
+    let (dx, dy) = (vent.to.x - vent.from.x, vent.to.y - vent.from.y);
+    dx != 0 && dx.abs() == dy.abs()
+}
+
+fn coordinate(input: &str) -> IResult<&str, i64> {
+    map_res(signed, |n| i32::try_from(n).map(i64::from))(input)
+}
+
+fn pos(input: &str) -> IResult<&str, Pos> {
+    map(tuple((coordinate, char(','), coordinate)), |(x, _, y)| {
+        Pos { x, y }
+    })(input)
+}
+
+/// Parse `x1,y1 -> x2,y2`, as a nom parser
+pub fn vent(input: &str) -> IResult<&str, Vent> {
+    map(
+        tuple((pos, space1, tag("->"), space1, pos)),
+        |(from, _, _, _, to)| Vent { from, to },
+    )(input)
+}
+
+pub fn parse_vent(input: &str) -> Option<Vent> {
+    let res: Result<_, E> = vent(input.trim());
+
+    match res {
+        Ok((_, vent)) => Some(vent),
+        Err(_) => None,
+    }
+}
+
+fn parse_vents(input: &Vec<&str>) -> Option<Vec<Vent>> {
+    input
+        .iter()
+        .filter(|s| !s.is_empty())
+        .map(|s| parse_vent(s))
+        .collect()
+}
+
+/// Draw a vent on a board, failing on the first position out of it
+fn draw_line(board: &mut [Vec<u8>], vent: &Vent) -> Result<(), Pos> {
+    for pos in positions(vent) {
+        let cell = usize::try_from(pos.y)
+            .ok()
+            .zip(usize::try_from(pos.x).ok())
+            .and_then(|(y, x)| board.get_mut(y)?.get_mut(x))
+            .ok_or(pos)?;
+        *cell += 1;
+    }
+    Ok(())
+}
+
+/// Draw vents on a board from the origin, failing on the first position out
+/// of it, such as a negative one
+pub fn draw_lines(board: &mut [Vec<u8>], vents: Vec<Vent>) -> Result<(), Pos> {
+    for vent in vents {
+        draw_line(board, &vent)?;
+    }
+    Ok(())
+}
+
+pub fn intersections(board: &Vec<Vec<u8>>) -> u32 {
+    let mut count = 0;
+    for row in board {
+        for cell in row {
+            if *cell >= 2 {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
+/// Number of positions covered by at least two vents in some raster mode,
+/// counting the vents on each position
+pub fn raster_overlaps(vents: &[Vent], raster: Raster) -> u64 {
+    let mut counts: HashMap<Pos, u64> = HashMap::new();
+    for vent in vents {
+        for pos in rasterize(vent, raster) {
+            *counts.entry(pos).or_default() += 1;
+        }
+    }
+    counts.values().filter(|n| **n >= 2).count() as u64
+}
+
+/// Primitive direction of the lines vents lie on, pointing to increasing x,
+/// or increasing y for vertical lines. Computations on lines use 128 bits so
+/// that they are exact for any 32 bits coordinates.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub(crate) struct Direction {
+    x: i128,
+    y: i128,
+}
+
+impl Direction {
+    fn of(vent: &Vent) -> Direction {
+        let (x, y) = match step(vent).0 {
+            (0, 0) => (1, 0),
+            (x, y) if x < 0 || (x == 0 && y < 0) => (-x, -y),
+            (x, y) => (x, y),
+        };
+        Direction {
+            x: x.into(),
+            y: y.into(),
+        }
+    }
+
+    /// Constant `c` of the line `y*X - x*Y = c` of this direction through a
+    /// point
+    fn offset(self, (x, y): (i128, i128)) -> i128 {
+        self.y * x - self.x * y
+    }
+
+    /// Index of a point along a line of this direction, consecutive integer
+    /// points of the line having consecutive indices
+    fn along(self, (x, y): (i128, i128)) -> i128 {
+        (self.x * x + self.y * y).div_euclid(self.x * self.x + self.y * self.y)
+    }
+}
+
+/// A line as its direction and offset
+pub(crate) type Line = (Direction, i128);
+
+/// Closed interval of indices along a line
+pub(crate) type Interval = (i128, i128);
+
+fn point(pos: Pos) -> (i128, i128) {
+    (pos.x.into(), pos.y.into())
+}
+
+/// The line of a vent and the interval it covers on it
+pub(crate) fn line(vent: &Vent) -> (Line, Interval) {
+    let direction = Direction::of(vent);
+    let (from, to) = (point(vent.from), point(vent.to));
+    let (t1, t2) = (direction.along(from), direction.along(to));
+    (
+        (direction, direction.offset(from)),
+        (t1.min(t2), t1.max(t2)),
+    )
+}
+
+/// Index along the line of a vent of a position on that line
+pub(crate) fn index_of(vent: &Vent, pos: Pos) -> i128 {
+    Direction::of(vent).along(point(pos))
+}
+
+/// Position at an index along the line of a vent, the inverse of `index_of`
+pub(crate) fn position_at(vent: &Vent, t: i128) -> Pos {
+    let direction = Direction::of(vent);
+    let from = point(vent.from);
+    let steps = t - direction.along(from);
+    Pos {
+        x: (from.0 + steps * direction.x) as i64,
+        y: (from.1 + steps * direction.y) as i64,
+    }
+}
+
+/// Sweep the endpoints of the intervals of a line, returning the maximal
+/// intervals covered by the same number of them, with that number
+pub(crate) fn depths(intervals: &[Interval]) -> Vec<(Interval, usize)> {
+    let mut events: Vec<(i128, i64)> = intervals
+        .iter()
+        .flat_map(|(lo, hi)| [(*lo, 1), (*hi + 1, -1)])
+        .collect();
+    events.sort_unstable();
+    let mut res: Vec<(Interval, usize)> = vec![];
+    let mut depth = 0;
+    let mut from = 0;
+    for (t, delta) in events {
+        if depth > 0 && t > from {
+            let n = depth as usize;
+            match res.last_mut() {
+                Some(((_, hi), m)) if *hi + 1 == from && *m == n => *hi = t - 1,
+                _ => res.push(((from, t - 1), n)),
+            }
+        }
+        depth += delta;
+        from = t;
+    }
+    res
+}
+
+/// The merged intervals of a line covered at least twice
+pub(crate) fn sweep(intervals: &[Interval]) -> Vec<Interval> {
+    let twice = depths(intervals)
+        .into_iter()
+        .filter(|(_, n)| *n > 1)
+        .map(|(interval, _)| interval)
+        .collect();
+    merge(twice)
+}
+
+/// Join touching intervals, sorted by start
+fn merge(intervals: Vec<Interval>) -> Vec<Interval> {
+    let mut res: Vec<Interval> = vec![];
+    for (lo, hi) in intervals {
+        match res.last_mut() {
+            Some(last) if lo <= last.1 + 1 => last.1 = last.1.max(hi),
+            _ => res.push((lo, hi)),
+        }
+    }
+    res
+}
+
+/// Whether sorted disjoint intervals cover an index
+pub(crate) fn covers(intervals: &[Interval], t: i128) -> bool {
+    let i = intervals.partition_point(|(_, hi)| *hi < t);
+    intervals.get(i).is_some_and(|(lo, _)| *lo <= t)
+}
+
+/// Number of integer points covered by at least two vents, without drawing
+/// them on a board: overlaps on a same line come from a sweep over the
+/// endpoints of its vents, and the other ones from the crossings of
+/// different lines found by the sweep of `vent_crossings`.
+pub fn overlaps(vents: &[Vent]) -> u64 {
+    let lines: Vec<(Line, Interval)> = vents.iter().map(line).collect();
+    let mut on_line: HashMap<Line, Vec<Interval>> = HashMap::new();
+    for (l, interval) in &lines {
+        on_line.entry(*l).or_default().push(*interval);
+    }
+    let twice: HashMap<Line, Vec<Interval>> = on_line
+        .into_iter()
+        .map(|(l, intervals)| (l, sweep(&intervals)))
+        .collect();
+
+    let mut count: u64 = twice
+        .values()
+        .flatten()
+        .map(|(lo, hi)| (hi - lo + 1) as u64)
+        .sum();
+    // a crossing is an overlap unless already counted on the lines of its
+    // vents, maybe several times
+    for c in intersect(vents).crossings {
+        let p = match c.point.to_pos() {
+            Some(p) => point(p),
+            None => continue,
+        };
+        let through: HashSet<Line> = c.vents.iter().map(|v| lines[*v].0).collect();
+        let overlapping = through
+            .into_iter()
+            .filter(|(d, offset)| {
+                twice
+                    .get(&(*d, *offset))
+                    .is_some_and(|covered| covers(covered, d.along(p)))
+            })
+            .count() as u64;
+        match overlapping {
+            0 => count += 1,
+            n => count -= n - 1,
+        }
+    }
+    count
+}
+
+/// Size of a board from the origin holding the vents, ignoring negative
+/// coordinates
+fn bounds(vents: &[Vent]) -> (usize, usize) {
+    let (x, y) = vents.iter().fold((0, 0), |(cur_x, cur_y), vent| {
+        (
+            max(max(cur_x, vent.from.x), vent.to.x),
+            max(max(cur_y, vent.from.y), vent.to.y),
+        )
+    });
+    (x as usize + 1, y as usize + 1)
+}
+
+pub fn parse(file: &str) -> Option<(Vec<Vent>, usize, usize)> {
+    if let Ok(input) = read_to_string(file) {
+        if let Some(vents) = parse_vents(&input.split("\n").collect::<Vec<&str>>()) {
+            let (max_x, max_y) = bounds(&vents);
+            return Some((vents, max_x, max_y));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_VENTS: [Vent; 10] = [
+        Vent {
+            from: Pos { x: 0, y: 9 },
+            to: Pos { x: 5, y: 9 },
+        },
+        Vent {
+            from: Pos { x: 8, y: 0 },
+            to: Pos { x: 0, y: 8 },
+        },
+        Vent {
+            from: Pos { x: 9, y: 4 },
+            to: Pos { x: 3, y: 4 },
+        },
+        Vent {
+            from: Pos { x: 2, y: 2 },
+            to: Pos { x: 2, y: 1 },
+        },
+        Vent {
+            from: Pos { x: 7, y: 0 },
+            to: Pos { x: 7, y: 4 },
+        },
+        Vent {
+            from: Pos { x: 6, y: 4 },
+            to: Pos { x: 2, y: 0 },
+        },
+        Vent {
+            from: Pos { x: 0, y: 9 },
+            to: Pos { x: 2, y: 9 },
+        },
+        Vent {
+            from: Pos { x: 3, y: 4 },
+            to: Pos { x: 1, y: 4 },
+        },
+        Vent {
+            from: Pos { x: 0, y: 0 },
+            to: Pos { x: 8, y: 8 },
+        },
+        Vent {
+            from: Pos { x: 5, y: 5 },
+            to: Pos { x: 8, y: 2 },
+        },
+    ];
+
+    #[test]
+    fn filter_orthogonal_vents() {
+        let sample_vents = SAMPLE_VENTS.to_vec();
+        assert_eq!(
+            sample_vents
+                .into_iter()
+                .filter(is_ortho)
+                .collect::<Vec<Vent>>()
+                .len(),
+            6
+        );
+    }
+
+    #[test]
+    fn retrieve_bounds_from_vents() {
+        assert_eq!(bounds(&SAMPLE_VENTS), (10, 10));
+    }
+
+    #[test]
+    fn can_draw_a_vertical_line() {
+        let vents = vec![Vent {
+            from: Pos { x: 1, y: 0 },
+            to: Pos { x: 1, y: 3 },
+        }];
+        let mut board = vec![vec![0; 4]; 4];
+
+        draw_lines(&mut board, vents).unwrap();
+
+        assert_eq!(
+            board,
+            [[0, 1, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0], [0, 1, 0, 0]]
+        );
+    }
+
+    #[test]
+    fn can_draw_a_horizontal_line() {
+        let vents = vec![Vent {
+            from: Pos { x: 0, y: 3 },
+            to: Pos { x: 3, y: 3 },
+        }];
+        let mut board = vec![vec![0; 4]; 4];
+
+        draw_lines(&mut board, vents).unwrap();
+
+        assert_eq!(
+            board,
+            [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 1, 1]]
+        );
+    }
+
+    #[test]
+    fn can_draw_a_horizontal_line_inverted() {
+        let vents = vec![Vent {
+            from: Pos { x: 3, y: 3 },
+            to: Pos { x: 0, y: 3 },
+        }];
+        let mut board = vec![vec![0; 4]; 4];
+
+        draw_lines(&mut board, vents).unwrap();
+
+        assert_eq!(
+            board,
+            [[0, 0, 0, 0], [0, 0, 0, 0], [0, 0, 0, 0], [1, 1, 1, 1]]
+        );
+    }
+
+    #[test]
+    fn reject_positions_out_of_the_board() {
+        let mut board = vec![vec![0; 4]; 4];
+        let vent = |x1, y1, x2, y2| Vent {
+            from: Pos { x: x1, y: y1 },
+            to: Pos { x: x2, y: y2 },
+        };
+
+        assert_eq!(
+            draw_lines(&mut board, vec![vent(1, 1, -1, -1)]),
+            Err(Pos { x: -1, y: -1 })
+        );
+        assert_eq!(
+            draw_lines(&mut board, vec![vent(0, 3, 1_000_000, 3)]),
+            Err(Pos { x: 4, y: 3 })
+        );
+    }
+
+    #[test]
+    fn can_compute_intersection() {
+        let mut board = vec![vec![0; 10]; 10];
+
+        draw_lines(
+            &mut board,
+            SAMPLE_VENTS.to_vec().into_iter().filter(is_ortho).collect(),
+        )
+        .unwrap();
+
+        assert_eq!(intersections(&board), 5);
+    }
+
+    #[test]
+    fn count_overlaps_without_a_board() {
+        let all = SAMPLE_VENTS.to_vec();
+        let ortho: Vec<Vent> = all.iter().cloned().filter(is_ortho).collect();
+
+        assert_eq!(overlaps(&ortho), 5);
+        assert_eq!(overlaps(&all), 12);
+        assert_eq!(overlaps(&[]), 0);
+    }
+
+    #[test]
+    fn count_overlaps_far_away_and_of_many_vents() {
+        let far = 1_000_000_000;
+        let vents = vec![
+            vent(far, far, far + 10, far),
+            vent(far + 5, far - 5, far + 5, far + 5),
+            vent(far + 3, far - 3, far + 9, far + 3),
+        ];
+        // each pair crosses at a different point
+        assert_eq!(overlaps(&vents), 3);
+
+        let same = vec![vent(0, 0, 3, 3); 300];
+        assert_eq!(overlaps(&same), 4);
+    }
+
+    #[test]
+    fn diagonals_crossing_between_points_do_not_overlap() {
+        assert_eq!(overlaps(&[vent(0, 0, 1, 1), vent(1, 0, 0, 1)]), 0);
+    }
+
+    fn vent(x1: i64, y1: i64, x2: i64, y2: i64) -> Vent {
+        Vent {
+            from: Pos { x: x1, y: y1 },
+            to: Pos { x: x2, y: y2 },
+        }
+    }
+
+    fn xy(positions: Vec<Pos>) -> Vec<(i64, i64)> {
+        positions.iter().map(|p| (p.x, p.y)).collect()
+    }
+
+    #[test]
+    fn rasterize_vents_in_any_direction() {
+        let steep = vent(0, 0, 3, 1);
+
+        assert_eq!(xy(positions(&steep)), [(0, 0), (3, 1)]);
+        assert_eq!(
+            xy(rasterize(&steep, Raster::Bresenham)),
+            [(0, 0), (1, 0), (2, 1), (3, 1)]
+        );
+        assert_eq!(
+            xy(positions(&vent(4, -2, 0, 0))),
+            [(4, -2), (2, -1), (0, 0)]
+        );
+        assert_eq!(xy(positions(&vent(1, 1, 1, 1))), [(1, 1)]);
+        for v in SAMPLE_VENTS.iter() {
+            assert_eq!(positions(v), rasterize(v, Raster::Bresenham));
+        }
+    }
+
+    #[test]
+    fn filter_diagonal_vents() {
+        assert_eq!(SAMPLE_VENTS.iter().filter(|v| is_diagonal(v)).count(), 4);
+        assert!(!is_diagonal(&vent(0, 0, 3, 1)));
+        assert!(!is_diagonal(&vent(2, 2, 2, 2)));
+    }
+
+    #[test]
+    fn count_overlaps_of_vents_in_any_direction() {
+        // the first and last vents share 3 integer points, one of them on
+        // the vertical vent
+        let vents = vec![vent(0, 0, 4, 2), vent(2, 0, 2, 5), vent(-2, -1, 6, 3)];
+        assert_eq!(overlaps(&vents), 3);
+
+        // these two cross between integer points, but Bresenham rounds the
+        // first one onto the second one
+        let vents = vec![vent(0, 0, 3, 1), vent(1, 0, 1, 3)];
+        assert_eq!(overlaps(&vents), 0);
+        assert_eq!(raster_overlaps(&vents, Raster::Lattice), 0);
+        assert_eq!(raster_overlaps(&vents, Raster::Bresenham), 1);
+    }
+
+    #[test]
+    fn parse_negative_coordinates() {
+        assert_eq!(parse_vent("-1,2 -> 3,-4"), Some(vent(-1, 2, 3, -4)));
+        assert_eq!(parse_vent("1,2 -> 3,4294967296"), None);
+    }
+
+    #[test]
+    fn can_parse_vents_from_a_vec_of_strings() {
+        let input = vec![
+            "0,9 -> 5,9",
+            "8,0 -> 0,8",
+            "9,4 -> 3,4",
+            "2,2 -> 2,1",
+            "7,0 -> 7,4",
+            "6,4 -> 2,0",
+            "0,9 -> 2,9",
+            "3,4 -> 1,4",
+            "0,0 -> 8,8",
+            "5,5 -> 8,2",
+        ];
+
+        let vents = parse_vents(&input);
+
+        assert_eq!(vents, Some(SAMPLE_VENTS.to_vec()));
+    }
+}